@@ -3,19 +3,219 @@
 mod endpoints;
 pub mod market_data;
 pub mod parameter;
+pub mod retry;
 pub mod trader;
 
+use futures_util::stream::{FuturesUnordered, Stream, StreamExt};
+use reqwest::header::HeaderMap;
 use reqwest::Client;
-
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::model::AccountNumbersExt;
+use crate::streamer::Streamer;
 use crate::token::Tokener;
 use crate::{error::Error, model};
-use parameter::{Market, Projection, TransactionType};
+use parameter::{Market, Projection, Symbol, TransactionType};
+use retry::{Refresher, RetryPolicy, Sender};
+
+/// Default timeout of a whole request, from connecting until the response body is read.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default timeout of the connect phase.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Host of the production Schwab API, every endpoint path is relative to it.
+pub const DEFAULT_BASE_URL: &str = "https://api.schwabapi.com";
+
+/// Max date range of a single request made by [`Api::get_all_account_transactions`] and [`Api::get_all_account_orders`].
+pub const HISTORY_WINDOW: chrono::TimeDelta = chrono::TimeDelta::days(30);
+
+/// Max orders or transactions Schwab returns for a single request.
+///
+/// A window of [`Api::get_all_account_orders`] or [`Api::get_all_account_transactions`] returning this many
+/// may have been truncated, so it is split in half and fetched again.
+pub const HISTORY_MAX_RESULTS: usize = 3000;
+
+/// Max symbols Schwab accepts in a single [`Api::get_quotes`] request, the default chunk size of [`Api::get_quotes_batched`].
+pub const QUOTES_CHUNK_SIZE: usize = 500;
+
+/// Shortest interval of [`Api::quote_stream`], a shorter one is raised to it.
+pub const MIN_QUOTE_STREAM_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Quotes fetched by [`Api::get_quotes_batched`].
+#[derive(Debug, Default)]
+pub struct QuotesBatch {
+    /// Quotes of every chunk that succeeded, by symbol.
+    pub quotes: HashMap<String, model::QuoteResponse>,
+    /// Chunks that failed, with the symbols they requested.
+    pub failed: Vec<(Vec<String>, Error)>,
+}
+
+/// Split `[start, end]` into consecutive windows of at most [`HISTORY_WINDOW`].
+fn history_windows(
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> impl Iterator<Item = (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
+    std::iter::successors(Some(start), |x| Some(*x + HISTORY_WINDOW))
+        .take_while(move |x| *x < end)
+        .map(move |x| (x, (x + HISTORY_WINDOW).min(end)))
+}
+
+/// Fetch every window of [`history_windows`] by `fetch`, in order.
+///
+/// A window returning `max_results` items or more is split in half instead, down to one second,
+/// since the response may have been truncated.
+async fn fetch_history<T, F, Fut>(
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+    max_results: usize,
+    mut fetch: F,
+) -> Result<Vec<T>, Error>
+where
+    F: FnMut(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, Error>>,
+{
+    // the earliest window last, to pop it first
+    let mut windows: Vec<_> = history_windows(start, end).collect();
+    windows.reverse();
+
+    let mut items = Vec::new();
+    while let Some((from, to)) = windows.pop() {
+        let batch = fetch(from, to).await?;
+        if batch.len() >= max_results {
+            let half = (to - from) / 2;
+            if half >= chrono::TimeDelta::seconds(1) {
+                windows.push((from + half, to));
+                windows.push((from, from + half));
+                continue;
+            }
+            warn!(
+                "{} items between {from} and {to}, some may be missing",
+                batch.len()
+            );
+        }
+        items.extend(batch);
+    }
+    Ok(items)
+}
+
+/// Default `User-Agent` of [`client_builder`], e.g. `schwab_api/0.1.0`.
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// A [`reqwest::ClientBuilder`] with [`DEFAULT_TIMEOUT`], [`DEFAULT_CONNECT_TIMEOUT`], and [`DEFAULT_USER_AGENT`].
+///
+/// Override them by [`reqwest::ClientBuilder::timeout`], [`reqwest::ClientBuilder::connect_timeout`], and [`reqwest::ClientBuilder::user_agent`].
+/// A request exceeding one of the timeouts fails with [`Error::Timeout`].
+///
+/// To identify the client on every request, see [`Api::client_headers`].
+pub fn client_builder() -> reqwest::ClientBuilder {
+    Client::builder()
+        .timeout(DEFAULT_TIMEOUT)
+        .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+        .user_agent(DEFAULT_USER_AGENT)
+}
+
+/// Build an [`Api`] with defaults for everything but the [`Tokener`], see [`Api::builder`].
+///
+/// The token is stored by the [`Tokener`] itself, e.g. at the path given to [`crate::token::TokenChecker::new`].
+#[derive(Debug)]
+pub struct ApiBuilder<T: Tokener> {
+    tokener: Option<T>,
+    base_url: String,
+    timeout: Duration,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    client_headers: HeaderMap,
+}
+
+impl<T: Tokener> Default for ApiBuilder<T> {
+    fn default() -> Self {
+        Self {
+            tokener: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            client_headers: HeaderMap::new(),
+        }
+    }
+}
+
+impl<T: Tokener> ApiBuilder<T> {
+    /// Required, hands out the access token of every request.
+    #[must_use]
+    pub fn tokener(mut self, val: T) -> Self {
+        self.tokener = Some(val);
+        self
+    }
+
+    /// Default is [`DEFAULT_BASE_URL`].
+    #[must_use]
+    pub fn base_url(mut self, val: impl Into<String>) -> Self {
+        self.base_url = val.into();
+        self
+    }
+
+    /// Timeout of a whole request, default is [`DEFAULT_TIMEOUT`].
+    #[must_use]
+    pub fn timeout(mut self, val: Duration) -> Self {
+        self.timeout = val;
+        self
+    }
+
+    /// Default is [`RetryPolicy::default`], see [`Api::retry_policy`].
+    #[must_use]
+    pub fn retry_policy(mut self, val: RetryPolicy) -> Self {
+        self.retry_policy = val;
+        self
+    }
+
+    /// Default is [`DEFAULT_USER_AGENT`].
+    #[must_use]
+    pub fn user_agent(mut self, val: impl Into<String>) -> Self {
+        self.user_agent = val.into();
+        self
+    }
+
+    /// Default is none, see [`Api::client_headers`].
+    #[must_use]
+    pub fn client_headers(mut self, val: HeaderMap) -> Self {
+        self.client_headers = val;
+        self
+    }
+
+    /// Fails with [`Error::InvalidConfig`] without a [`Self::tokener`], otherwise as [`Api::with_base_url`].
+    pub async fn build(self) -> Result<Api<T>, Error> {
+        let tokener = self
+            .tokener
+            .ok_or_else(|| Error::InvalidConfig("tokener is required".to_string()))?;
+        let client = client_builder()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent)
+            .build()?;
+
+        let mut api = Api::unchecked(tokener, client, self.base_url);
+        api.retry_policy(self.retry_policy)
+            .client_headers(self.client_headers);
+        api.check_token().await?;
+        Ok(api)
+    }
+}
 
 /// Interacting with the Schwab API.
 #[derive(Debug)]
 pub struct Api<T: Tokener> {
-    tokener: T,
+    tokener: Arc<T>,
     client: Client,
+    retry_policy: RetryPolicy,
+    client_headers: HeaderMap,
+    base_url: String,
+    /// Cached by [`Api::account_hash`]
+    account_numbers: Mutex<Option<model::AccountNumbers>>,
 }
 
 impl<T: Tokener> Api<T> {
@@ -24,62 +224,249 @@ impl<T: Tokener> Api<T> {
     ///
     /// Will panic if no symbol found
     pub async fn new(tokener: T, client: Client) -> Result<Self, Error> {
-        let api = Api { tokener, client };
+        Self::with_base_url(tokener, client, DEFAULT_BASE_URL).await
+    }
 
-        if (api.get_quote("AAPL".to_string()).await?.send().await).is_err() {
-            api.tokener.redo_authorization().await?;
-        }
+    /// Create API Struct with a client from [`client_builder`]
+    pub async fn with_tokener(tokener: T) -> Result<Self, Error> {
+        Self::builder().tokener(tokener).build().await
+    }
 
+    /// Set the tokener and override the defaults of the base URL, timeout, retry policy, or User-Agent
+    ///
+    /// ```no_run
+    /// # async fn f(checker: schwab_api::token::TokenChecker) -> Result<(), schwab_api::Error> {
+    /// use schwab_api::api::{retry::RetryPolicy, Api};
+    ///
+    /// let api = Api::builder()
+    ///     .tokener(checker)
+    ///     .timeout(std::time::Duration::from_secs(10))
+    ///     .retry_policy(RetryPolicy::none())
+    ///     .user_agent("my-dashboard/1.0")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn builder() -> ApiBuilder<T> {
+        ApiBuilder::default()
+    }
+
+    /// Create API Struct against another host than [`DEFAULT_BASE_URL`], e.g. a sandbox or a mock server
+    pub async fn with_base_url(
+        tokener: T,
+        client: Client,
+        base_url: impl Into<String>,
+    ) -> Result<Self, Error> {
+        let api = Self::unchecked(tokener, client, base_url);
+        api.check_token().await?;
         Ok(api)
     }
 
+    fn unchecked(tokener: T, client: Client, base_url: impl Into<String>) -> Self {
+        Api {
+            tokener: Arc::new(tokener),
+            client,
+            retry_policy: RetryPolicy::default(),
+            client_headers: HeaderMap::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            account_numbers: Mutex::new(None),
+        }
+    }
+
+    /// Authorize again if a quote can't be requested with the token.
+    async fn check_token(&self) -> Result<(), Error> {
+        if (self.get_quote("AAPL").await?.send().await).is_err() {
+            self.tokener.redo_authorization().await?;
+        }
+        Ok(())
+    }
+
+    /// Retry policy for transient failures, applied to every request except placing, replacing and previewing orders.
+    ///
+    /// To retry those as well, set it on the returned request explicitly.
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.retry_policy = val;
+        self
+    }
+
+    /// Headers added to every request, e.g. [`model::trader::user_preference::StreamerInfo::client_headers`]
+    /// to identify the client by `Schwab-Client-CorrelId` and `Schwab-Client-Channel`.
+    ///
+    /// ```no_run
+    /// # async fn f(mut api: schwab_api::Api<schwab_api::token::TokenChecker>) -> Result<(), schwab_api::Error> {
+    /// let info = api.streamer_info().await?;
+    /// api.client_headers(info.client_headers());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn client_headers(&mut self, val: HeaderMap) -> &mut Self {
+        self.client_headers = val;
+        self
+    }
+
+    /// Refreshes the access token by [`Tokener::refresh_access_token`] when a request fails with `401 Unauthorized`.
+    fn refresher(&self) -> Refresher {
+        Refresher::new(self.tokener.clone())
+    }
+
+    /// Sends every request with [`Self::client_headers`] by [`Self::retry_policy`], and once more after [`Self::refresher`] on `401 Unauthorized`.
+    fn sender(&self) -> Sender {
+        Sender {
+            retry_policy: self.retry_policy,
+            refresher: Some(self.refresher()),
+            headers: self.client_headers.clone(),
+        }
+    }
+
+    /// Each symbol is validated by [`Symbol::new`] before sending.
+    ///
+    /// Use [`market_data::GetQuotesRequest::fields`] on the returned request to limit the response to a subset of root nodes,
+    /// and [`market_data::GetQuotesRequest::indicative`] to also get the indicative quote `$ABC.IV` of each ETF `ABC`.
     pub async fn get_quotes(
         &self,
-        symbols: Vec<String>,
+        symbols: impl IntoIterator<Item = impl Into<Symbol>>,
     ) -> Result<market_data::GetQuotesRequest, Error> {
+        let symbols = symbols
+            .into_iter()
+            .map(|x| Symbol::new(x.into()).map(String::from))
+            .collect::<Result<Vec<_>, _>>()?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetQuotesRequest::new(
-            &self.client,
-            access_token,
-            symbols,
-        ))
+        let mut req =
+            market_data::GetQuotesRequest::new(&self.client, &self.base_url, access_token, symbols);
+        req.sender = self.sender();
+        Ok(req)
     }
 
-    pub async fn get_quote(&self, symbol: String) -> Result<market_data::GetQuoteRequest, Error> {
+    /// Fetch a large universe of symbols as chunks of `chunk_size` (`None` for [`QUOTES_CHUNK_SIZE`]), at most `max_concurrency` chunks at a time.
+    ///
+    /// Each symbol is validated by [`Symbol::new`] before sending anything.
+    /// A failing chunk doesn't fail the batch, it is returned in [`QuotesBatch::failed`].
+    pub async fn get_quotes_batched(
+        &self,
+        symbols: impl IntoIterator<Item = impl Into<Symbol>>,
+        chunk_size: Option<usize>,
+        max_concurrency: usize,
+    ) -> Result<QuotesBatch, Error> {
+        let symbols = symbols
+            .into_iter()
+            .map(|x| Symbol::new(x.into()).map(String::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut chunks = symbols.chunks(chunk_size.unwrap_or(QUOTES_CHUNK_SIZE).max(1));
+
+        let fetch = |chunk: &[String]| {
+            let chunk = chunk.to_vec();
+            async move {
+                let result = match self.get_quotes(chunk.clone()).await {
+                    Ok(req) => req.send().await,
+                    Err(e) => Err(e),
+                };
+                (chunk, result)
+            }
+        };
+        let mut pending = chunks
+            .by_ref()
+            .take(max_concurrency.max(1))
+            .map(fetch)
+            .collect::<FuturesUnordered<_>>();
+
+        let mut batch = QuotesBatch::default();
+        while let Some((chunk, result)) = pending.next().await {
+            match result {
+                Ok(quotes) => batch.quotes.extend(quotes),
+                Err(e) => batch.failed.push((chunk, e)),
+            }
+            if let Some(chunk) = chunks.next() {
+                pending.push(fetch(chunk));
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Poll the quotes of `symbols` every `interval`, starting right away, e.g. for a dashboard without [`Self::streamer`].
+    ///
+    /// The token is refreshed as needed on each poll, and once more on `401 Unauthorized` like for every request.
+    /// A failed poll is yielded as an error item, and the stream goes on with the next tick.
+    /// Ticks missed while a poll is slow are skipped.
+    /// An `interval` shorter than [`MIN_QUOTE_STREAM_INTERVAL`], e.g. zero, is raised to it.
+    pub fn quote_stream(
+        &self,
+        symbols: Vec<String>,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<HashMap<String, model::QuoteResponse>, Error>> + '_ {
+        let mut interval = tokio::time::interval(interval.max(MIN_QUOTE_STREAM_INTERVAL));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        futures_util::stream::unfold(
+            (interval, symbols),
+            move |(mut interval, symbols)| async move {
+                interval.tick().await;
+                let result = async {
+                    self.get_quotes(symbols.iter().map(String::as_str))
+                        .await?
+                        .send()
+                        .await
+                }
+                .await;
+                Some((result, (interval, symbols)))
+            },
+        )
+    }
+
+    /// The symbol is validated by [`Symbol::new`] before sending.
+    ///
+    /// Use [`market_data::GetQuoteRequest::fields`] on the returned request to limit the response to a subset of root nodes.
+    pub async fn get_quote(
+        &self,
+        symbol: impl Into<Symbol>,
+    ) -> Result<market_data::GetQuoteRequest, Error> {
+        let symbol = Symbol::new(symbol.into())?.into();
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetQuoteRequest::new(
-            &self.client,
-            access_token,
-            symbol,
-        ))
+        let mut req =
+            market_data::GetQuoteRequest::new(&self.client, &self.base_url, access_token, symbol);
+        req.sender = self.sender();
+        Ok(req)
     }
 
+    /// The symbol is validated by [`Symbol::new`] before sending.
     pub async fn get_option_chains(
         &self,
-        symbol: String,
+        symbol: impl Into<Symbol>,
     ) -> Result<market_data::GetOptionChainsRequest, Error> {
+        let symbol = Symbol::new(symbol.into())?.into();
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetOptionChainsRequest::new(
+        let mut req = market_data::GetOptionChainsRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             symbol,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
+    /// Only the expirations of the option chain, much cheaper than [`Self::get_option_chains`] when only the available dates are needed.
+    ///
+    /// The symbol is validated by [`Symbol::new`] before sending.
     pub async fn get_option_expiration_chain(
         &self,
-        symbol: String,
+        symbol: impl Into<Symbol>,
     ) -> Result<market_data::GetOptionExpirationChainRequest, Error> {
+        let symbol = Symbol::new(symbol.into())?.into();
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetOptionExpirationChainRequest::new(
+        let mut req = market_data::GetOptionExpirationChainRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             symbol,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     pub async fn get_price_history(
@@ -88,11 +475,14 @@ impl<T: Tokener> Api<T> {
     ) -> Result<market_data::GetPriceHistoryRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetPriceHistoryRequest::new(
+        let mut req = market_data::GetPriceHistoryRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             symbol,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `symbol`
@@ -105,11 +495,10 @@ impl<T: Tokener> Api<T> {
     pub async fn get_movers(&self, symbol: String) -> Result<market_data::GetMoversRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetMoversRequest::new(
-            &self.client,
-            access_token,
-            symbol,
-        ))
+        let mut req =
+            market_data::GetMoversRequest::new(&self.client, &self.base_url, access_token, symbol);
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `markets`
@@ -123,11 +512,27 @@ impl<T: Tokener> Api<T> {
     ) -> Result<market_data::GetMarketsRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetMarketsRequest::new(
+        let mut req = market_data::GetMarketsRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             markets,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
+    }
+
+    /// Hours of `markets` on `date` instead of today, e.g. to schedule next Monday's session ahead of time.
+    ///
+    /// Same as [`Self::get_markets`] with [`market_data::GetMarketsRequest::date`], Schwab accepts dates up to 1 year from today.
+    pub async fn get_market_hours_for_date(
+        &self,
+        markets: Vec<Market>,
+        date: chrono::NaiveDate,
+    ) -> Result<model::Markets, Error> {
+        let mut req = self.get_markets(markets).await?;
+        req.date(date);
+        req.send().await
     }
 
     /// `market_id`
@@ -139,11 +544,14 @@ impl<T: Tokener> Api<T> {
     ) -> Result<market_data::GetMarketRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetMarketRequest::new(
+        let mut req = market_data::GetMarketRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             market_id,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `projection`
@@ -158,12 +566,15 @@ impl<T: Tokener> Api<T> {
     ) -> Result<market_data::GetInstrumentsRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetInstrumentsRequest::new(
+        let mut req = market_data::GetInstrumentsRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             symbol,
             projection,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `cusip_id`
@@ -175,41 +586,88 @@ impl<T: Tokener> Api<T> {
     ) -> Result<market_data::GetInstrumentRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(market_data::GetInstrumentRequest::new(
+        let mut req = market_data::GetInstrumentRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             cusip_id,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     pub async fn get_account_numbers(&self) -> Result<trader::GetAccountNumbersRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountNumbersRequest::new(
-            &self.client,
-            access_token,
-        ))
+        let mut req =
+            trader::GetAccountNumbersRequest::new(&self.client, &self.base_url, access_token);
+        req.sender = self.sender();
+        Ok(req)
+    }
+
+    /// The hashed value of a plain account number, which other trader endpoints require.
+    ///
+    /// The account numbers are fetched on first use and cached, see [`Self::refresh_account_numbers`].
+    pub async fn account_hash(&self, account_number: &str) -> Result<String, Error> {
+        let mut account_numbers = self.account_numbers.lock().await;
+        if account_numbers.is_none() {
+            *account_numbers = Some(self.get_account_numbers().await?.send().await?);
+        }
+
+        account_numbers
+            .as_ref()
+            .and_then(|x| x.hash_for(account_number))
+            .map(ToString::to_string)
+            .ok_or_else(|| Error::Account(format!("{account_number} not found")))
+    }
+
+    /// Reload the account numbers cached by [`Self::account_hash`].
+    pub async fn refresh_account_numbers(&self) -> Result<model::AccountNumbers, Error> {
+        let account_numbers = self.get_account_numbers().await?.send().await?;
+        *self.account_numbers.lock().await = Some(account_numbers.clone());
+        Ok(account_numbers)
+    }
+
+    /// A plain account number is all digits, unlike its hashed value.
+    async fn resolve_account_number(&self, account_number: String) -> Result<String, Error> {
+        if !account_number.is_empty() && account_number.bytes().all(|x| x.is_ascii_digit()) {
+            return self.account_hash(&account_number).await;
+        }
+        Ok(account_number)
     }
 
     pub async fn get_accounts(&self) -> Result<trader::GetAccountsRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountsRequest::new(&self.client, access_token))
+        let mut req = trader::GetAccountsRequest::new(&self.client, &self.base_url, access_token);
+        req.sender = self.sender();
+        Ok(req)
     }
 
+    /// `account_number`
+    ///
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     pub async fn get_account(
         &self,
         account_number: String,
     ) -> Result<trader::GetAccountRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountRequest::new(
+        let mut req = trader::GetAccountRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
+    /// `account_number`
+    ///
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
+    ///
     /// `from_entered_time`
     ///
     /// Specifies that no orders entered before this time should be returned.
@@ -225,38 +683,85 @@ impl<T: Tokener> Api<T> {
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
     ) -> Result<trader::GetAccountOrdersRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountOrdersRequest::new(
+        let mut req = trader::GetAccountOrdersRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             from_entered_time,
             to_entered_time,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     pub async fn post_account_order(
         &self,
         account_number: String,
         body: model::OrderRequest,
     ) -> Result<trader::PostAccountOrderRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::PostAccountOrderRequest::new(
+        let mut req = trader::PostAccountOrderRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             body,
-        ))
+        );
+        // not retried by default, see `Self::retry_policy`
+        req.sender = Sender {
+            retry_policy: RetryPolicy::none(),
+            ..self.sender()
+        };
+        Ok(req)
+    }
+
+    /// All orders entered between `from_entered_time` and `to_entered_time`, fetched in windows of [`HISTORY_WINDOW`].
+    ///
+    /// A window returning [`HISTORY_MAX_RESULTS`] orders is split in half and fetched again.
+    /// Orders returned by more than one window are only kept once, by `order_id`.
+    ///
+    /// See [`Self::get_account_orders`] for the parameters.
+    pub async fn get_all_account_orders(
+        &self,
+        account_number: String,
+        from_entered_time: chrono::DateTime<chrono::Utc>,
+        to_entered_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<model::Order>, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
+
+        let mut orders = fetch_history(
+            from_entered_time,
+            to_entered_time,
+            HISTORY_MAX_RESULTS,
+            |from, to| {
+                let account_number = account_number.clone();
+                async move {
+                    self.get_account_orders(account_number, from, to)
+                        .await?
+                        .send()
+                        .await
+                }
+            },
+        )
+        .await?;
+
+        let mut seen = HashSet::new();
+        orders.retain(|x| seen.insert(x.order_id));
+        Ok(orders)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     ///
     /// `order_id`
     ///
@@ -266,19 +771,59 @@ impl<T: Tokener> Api<T> {
         account_number: String,
         order_id: i64,
     ) -> Result<trader::GetAccountOrderRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountOrderRequest::new(
+        let mut req = trader::GetAccountOrderRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             order_id,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
+    }
+
+    /// Poll [`Self::get_account_order`] every `poll_interval` until the order is filled, returning the final order.
+    ///
+    /// Fails with [`Error::OrderNotFilled`] if the order ends up e.g. `REJECTED` or `CANCELED`,
+    /// and with [`Error::OrderWaitTimeout`] if it is still open after `timeout`.
+    /// Both carry the last polled order.
+    pub async fn wait_for_fill(
+        &self,
+        account_number: String,
+        order_id: i64,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<model::Order, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let order = self
+                .get_account_order(account_number.clone(), order_id)
+                .await?
+                .send()
+                .await?;
+            if order.is_filled() {
+                return Ok(order);
+            }
+            if order.is_terminal() {
+                return Err(Error::OrderNotFilled(Box::new(order)));
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(Error::OrderWaitTimeout(Box::new(order)));
+            }
+            tokio::time::sleep(poll_interval.min(deadline - now)).await;
+        }
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     ///
     /// `order_id`
     ///
@@ -288,19 +833,23 @@ impl<T: Tokener> Api<T> {
         account_number: String,
         order_id: i64,
     ) -> Result<trader::DeleteAccountOrderRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::DeleteAccountOrderRequest::new(
+        let mut req = trader::DeleteAccountOrderRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             order_id,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     ///
     /// `order_id`
     ///
@@ -311,15 +860,23 @@ impl<T: Tokener> Api<T> {
         order_id: i64,
         body: model::OrderRequest,
     ) -> Result<trader::PutAccountOrderRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::PutAccountOrderRequest::new(
+        let mut req = trader::PutAccountOrderRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             order_id,
             body,
-        ))
+        );
+        // not retried by default, see `Self::retry_policy`
+        req.sender = Sender {
+            retry_policy: RetryPolicy::none(),
+            ..self.sender()
+        };
+        Ok(req)
     }
 
     /// `from_entered_time`
@@ -338,35 +895,50 @@ impl<T: Tokener> Api<T> {
     ) -> Result<trader::GetAccountsOrdersRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountsOrdersRequest::new(
+        let mut req = trader::GetAccountsOrdersRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             from_entered_time,
             to_entered_time,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
+    ///
+    /// `body`
+    ///
+    /// The same order as for [`Self::post_account_order`], which is validated but not placed.
     pub async fn post_accounts_preview_order(
         &self,
         account_number: String,
-        body: model::PreviewOrder,
+        body: model::OrderRequest,
     ) -> Result<trader::PostAccountPreviewOrderRequest, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::PostAccountPreviewOrderRequest::new(
+        let mut req = trader::PostAccountPreviewOrderRequest::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             body,
-        ))
+        );
+        // not retried by default, see `Self::retry_policy`
+        req.sender = Sender {
+            retry_policy: RetryPolicy::none(),
+            ..self.sender()
+        };
+        Ok(req)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     ///
     /// `start_date`
     ///
@@ -390,21 +962,57 @@ impl<T: Tokener> Api<T> {
         end_date: chrono::DateTime<chrono::Utc>,
         types: TransactionType,
     ) -> Result<trader::GetAccountTransactions, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountTransactions::new(
+        let mut req = trader::GetAccountTransactions::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             start_date,
             end_date,
             types,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
+    }
+
+    /// All transactions between `start_date` and `end_date`, fetched in windows of [`HISTORY_WINDOW`].
+    ///
+    /// A window returning [`HISTORY_MAX_RESULTS`] transactions is split in half and fetched again.
+    /// Transactions returned by more than one window are only kept once, by `activity_id`.
+    ///
+    /// See [`Self::get_account_transactions`] for the parameters.
+    pub async fn get_all_account_transactions(
+        &self,
+        account_number: String,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        types: TransactionType,
+    ) -> Result<Vec<model::Transaction>, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
+
+        let mut transactions =
+            fetch_history(start_date, end_date, HISTORY_MAX_RESULTS, |start, end| {
+                let account_number = account_number.clone();
+                async move {
+                    self.get_account_transactions(account_number, start, end, types)
+                        .await?
+                        .send()
+                        .await
+                }
+            })
+            .await?;
+
+        let mut seen = HashSet::new();
+        transactions.retain(|x| seen.insert(x.activity_id));
+        Ok(transactions)
     }
 
     /// `account_number`
     ///
-    /// The encrypted ID of the account
+    /// The encrypted ID of the account, or the plain account number resolved by [`Self::account_hash`]
     ///
     /// `transaction_id`
     ///
@@ -414,23 +1022,48 @@ impl<T: Tokener> Api<T> {
         account_number: String,
         transaction_id: i64,
     ) -> Result<trader::GetAccountTransaction, Error> {
+        let account_number = self.resolve_account_number(account_number).await?;
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountTransaction::new(
+        let mut req = trader::GetAccountTransaction::new(
             &self.client,
+            &self.base_url,
             access_token,
             account_number,
             transaction_id,
-        ))
+        );
+        req.sender = self.sender();
+        Ok(req)
     }
 
     pub async fn get_user_preference(&self) -> Result<trader::GetUserPreferenceRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetUserPreferenceRequest::new(
-            &self.client,
-            access_token,
-        ))
+        let mut req =
+            trader::GetUserPreferenceRequest::new(&self.client, &self.base_url, access_token);
+        req.sender = self.sender();
+        Ok(req)
+    }
+
+    /// The first streamer info found in the user preference.
+    pub async fn streamer_info(
+        &self,
+    ) -> Result<model::trader::user_preference::StreamerInfo, Error> {
+        match self.get_user_preference().await?.send().await? {
+            model::UserPreferences::One(preference) => preference.streamer_info.into_iter().next(),
+            model::UserPreferences::Mutiple(preferences) => {
+                preferences.into_iter().flat_map(|x| x.streamer_info).next()
+            }
+        }
+        .ok_or_else(|| Error::Streamer("no streamer info in user preference".to_string()))
+    }
+
+    /// Connect and log in to the streamer found in the user preference, see [`Self::streamer_info`].
+    pub async fn streamer(&self) -> Result<Streamer, Error> {
+        let info = self.streamer_info().await?;
+        let access_token = self.tokener.get_access_token().await?;
+
+        Streamer::connect(info, access_token).await
     }
 }
 
@@ -438,6 +1071,7 @@ impl<T: Tokener> Api<T> {
 mod tests {
     use super::*;
 
+    use chrono::TimeDelta;
     use float_cmp::assert_approx_eq;
     use pretty_assertions::assert_eq;
     use std::path::PathBuf;
@@ -466,7 +1100,7 @@ mod tests {
 
         let callback_url = "https://127.0.0.1:8080".to_string();
 
-        let client = Client::new();
+        let client = client_builder().build().unwrap();
         let token_checker =
             TokenChecker::new(path, key, secret, callback_url, certs_dir, client.clone())
                 .await
@@ -475,6 +1109,73 @@ mod tests {
         Api::new(token_checker, client).await.unwrap()
     }
 
+    #[test]
+    fn test_history_windows() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let windows: Vec<_> = history_windows(start, start + TimeDelta::days(75)).collect();
+        assert_eq!(
+            windows,
+            vec![
+                (start, start + TimeDelta::days(30)),
+                (start + TimeDelta::days(30), start + TimeDelta::days(60)),
+                (start + TimeDelta::days(60), start + TimeDelta::days(75)),
+            ]
+        );
+
+        let windows: Vec<_> = history_windows(start, start + TimeDelta::days(30)).collect();
+        assert_eq!(windows, vec![(start, start + TimeDelta::days(30))]);
+
+        assert_eq!(history_windows(start, start).count(), 0);
+        assert_eq!(
+            history_windows(start, start - TimeDelta::days(1)).count(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_history_split() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let end = start + TimeDelta::days(40);
+        let requested = std::sync::Mutex::new(Vec::new());
+
+        // the first window is full, so fetched again in two halves
+        let items = fetch_history(start, end, 2, |from, to| {
+            requested.lock().unwrap().push((from, to));
+            let len = if to - from == TimeDelta::days(30) {
+                2
+            } else {
+                1
+            };
+            async move { Ok(vec![from; len]) }
+        })
+        .await
+        .unwrap();
+
+        let mid = start + TimeDelta::days(15);
+        assert_eq!(
+            requested.into_inner().unwrap(),
+            vec![
+                (start, start + TimeDelta::days(30)),
+                (start, mid),
+                (mid, start + TimeDelta::days(30)),
+                (start + TimeDelta::days(30), end),
+            ]
+        );
+        assert_eq!(items, vec![start, mid, start + TimeDelta::days(30)]);
+
+        // a window can't be split below one second
+        let items = fetch_history(
+            start,
+            start + TimeDelta::seconds(1),
+            2,
+            |from, _| async move { Ok(vec![from; 3]) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(items.len(), 3);
+    }
+
     #[cfg_attr(
         not(feature = "test_online"),
         ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
@@ -557,7 +1258,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_option_chains() {
         let api = client().await;
-        let mut req = api.get_option_chains("AAPL".into()).await.unwrap();
+        let mut req = api.get_option_chains("AAPL").await.unwrap();
         req.days_to_expiration(3)
             .exp_month(parameter::Month::All)
             .contract_type(parameter::ContractType::All);
@@ -584,10 +1285,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_option_expiration_chain() {
         let api = client().await;
-        let req = api
-            .get_option_expiration_chain("AAPL".into())
-            .await
-            .unwrap();
+        let req = api.get_option_expiration_chain("AAPL").await.unwrap();
         let rsp = req.send().await.unwrap();
         dbg!(rsp);
     }
@@ -696,6 +1394,35 @@ mod tests {
         dbg!(rsp);
     }
 
+    #[cfg_attr(
+        not(feature = "test_online"),
+        ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
+    )]
+    #[tokio::test]
+    async fn test_account_hash() {
+        let api = client().await;
+        let account_numbers = api.refresh_account_numbers().await.unwrap();
+        let plain = &account_numbers[0].account_number;
+
+        let hash = api.account_hash(plain).await.unwrap();
+        assert_eq!(hash, account_numbers[0].hash_value);
+
+        // plain account numbers are resolved transparently
+        let rsp = api
+            .get_account(plain.clone())
+            .await
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        dbg!(rsp);
+
+        assert!(matches!(
+            api.account_hash("0").await,
+            Err(Error::Account(_))
+        ));
+    }
+
     async fn account_number() -> String {
         let api = client().await;
         let req = api.get_account_numbers().await.unwrap();
@@ -784,7 +1511,7 @@ mod tests {
         not(all(feature = "test_online", feature = "danger")),
         ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
     )]
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::float_cmp)]
     #[tokio::test]
     async fn test_post_put_delete_account_order() {
         let api = client().await;
@@ -793,8 +1520,8 @@ mod tests {
             symbol: "VEA".to_string(),
         };
         let quantity = 1.0;
-        let price = 10.0;
-        let modified_price = 11.0;
+        let price = model::price(10.0);
+        let modified_price = model::price(11.0);
 
         // post
         let order_post =
@@ -827,7 +1554,7 @@ mod tests {
             order_post_check.session,
             model::trader::order::Session::Normal
         );
-        assert_approx_eq!(f64, order_post_check.price, price);
+        assert_eq!(order_post_check.price, price);
         assert_eq!(
             order_post_check.duration,
             model::trader::order::Duration::Day
@@ -860,10 +1587,9 @@ mod tests {
             .put_account_order(account_number().await, order_id, order_put.clone())
             .await
             .unwrap();
-        req.send().await.unwrap();
+        let order_id = req.send().await.unwrap();
 
         // put check
-        let order_id = order_id + 1;
         let req = api
             .get_account_order(account_number().await, order_id)
             .await
@@ -874,7 +1600,7 @@ mod tests {
             order_put_check.session,
             model::trader::order::Session::Normal
         );
-        assert_approx_eq!(f64, order_put_check.price, modified_price);
+        assert_eq!(order_put_check.price, modified_price);
         assert_eq!(
             order_put_check.duration,
             model::trader::order::Duration::Day
@@ -963,19 +1689,25 @@ mod tests {
     }
 
     #[cfg_attr(
-        not(all(feature = "test_online", feature = "danger")),
+        not(feature = "test_online"),
         ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
     )]
     #[tokio::test]
     async fn test_post_accounts_preview_order() {
-        unimplemented!("comming soon by schwab");
-        // let api = client().await;
-        // let req = api
-        //     .post_accounts_preview_order(account_number().await, model::PreviewOrder::default())
-        //     .await
-        //     .unwrap();
-        // let rsp = req.send().await.unwrap();
-        // dbg!(rsp);
+        let api = client().await;
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "VEA".to_string(),
+        };
+        let order =
+            model::OrderRequest::limit(symbol, Instruction::Buy, 1.0, model::price(10.0)).unwrap();
+        let req = api
+            .post_accounts_preview_order(account_number().await, order)
+            .await
+            .unwrap();
+        let rsp = req.send().await.unwrap();
+        dbg!(&rsp);
+        dbg!(rsp.commission(), rsp.fees(), rsp.is_rejected());
     }
 
     #[cfg_attr(
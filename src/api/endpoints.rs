@@ -3,8 +3,8 @@ use urlencoding::encode;
 
 use super::parameter::Market;
 
-const SERVER_TRADER: &str = "https://api.schwabapi.com/trader/v1";
-const SERVER_MARKETDATA: &str = "https://api.schwabapi.com/marketdata/v1";
+const SERVER_TRADER: &str = "/trader/v1";
+const SERVER_MARKETDATA: &str = "/marketdata/v1";
 
 #[derive(Debug)]
 pub(crate) enum EndpointAccount {
@@ -37,9 +37,9 @@ impl EndpointAccount {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_TRADER}{}", self.url_endpoint())
     }
 }
 
@@ -76,7 +76,7 @@ pub(crate) enum EndpointOrder {
 
     // POST
     // /accounts/{accountNumber}/previewOrder
-    // Preview order for a specific account. **Coming Soon**.
+    // Preview order for a specific account.
     PreviewOrderAccount {
         account_number: String,
     },
@@ -105,9 +105,9 @@ impl EndpointOrder {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_TRADER}{}", self.url_endpoint())
     }
 }
 
@@ -147,9 +147,9 @@ impl EndpointTransaction {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_TRADER}{}", self.url_endpoint())
     }
 }
 
@@ -169,9 +169,9 @@ impl EndpointUserPreference {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_TRADER}{}", self.url_endpoint())
     }
 }
 
@@ -200,9 +200,9 @@ impl EndpointQuote {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -222,9 +222,9 @@ impl EndpointOptionChain {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -244,9 +244,9 @@ impl EndpointOptionExpirationChain {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -266,9 +266,9 @@ impl EndpointPriceHistory {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -291,9 +291,9 @@ impl EndpointMover {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -324,9 +324,9 @@ impl EndpointMarketHour {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -355,9 +355,9 @@ impl EndpointInstrument {
         }
     }
 
-    /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    /// defines the URL include server, e.g. [`crate::api::DEFAULT_BASE_URL`]
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        format!("{base_url}{SERVER_MARKETDATA}{}", self.url_endpoint())
     }
 }
 
@@ -365,18 +365,20 @@ impl EndpointInstrument {
 mod tests {
     use super::*;
 
+    use crate::api::DEFAULT_BASE_URL;
+
     use pretty_assertions::assert_eq;
 
     #[test]
     fn test_endpoint_account() {
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/accounts/accountNumbers",
-            EndpointAccount::AccountNumbers.url()
+            EndpointAccount::AccountNumbers.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/accounts",
-            EndpointAccount::Accounts.url()
+            EndpointAccount::Accounts.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -384,7 +386,7 @@ mod tests {
             EndpointAccount::Account {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -395,7 +397,7 @@ mod tests {
             EndpointOrder::OrdersAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -404,12 +406,12 @@ mod tests {
                 account_number: "123456".to_string(),
                 order_id: 789
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/orders",
-            EndpointOrder::Orders.url()
+            EndpointOrder::Orders.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -417,7 +419,7 @@ mod tests {
             EndpointOrder::PreviewOrderAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -428,7 +430,7 @@ mod tests {
             EndpointTransaction::TransactionsAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -437,7 +439,7 @@ mod tests {
                 account_number: "123456".to_string(),
                 transaction_id: 789
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -445,7 +447,7 @@ mod tests {
     fn test_endpoint_user_preference() {
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/userPreference",
-            EndpointUserPreference::UserPreference.url()
+            EndpointUserPreference::UserPreference.url(DEFAULT_BASE_URL)
         );
     }
 
@@ -453,7 +455,7 @@ mod tests {
     fn test_endpoint_quote() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/quotes",
-            EndpointQuote::Quotes.url()
+            EndpointQuote::Quotes.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -461,7 +463,7 @@ mod tests {
             EndpointQuote::Quote {
                 symbol_id: "ABC".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -469,7 +471,7 @@ mod tests {
     fn test_endpoint_option_chain() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/chains",
-            EndpointOptionChain::Chains.url()
+            EndpointOptionChain::Chains.url(DEFAULT_BASE_URL)
         );
     }
 
@@ -477,7 +479,7 @@ mod tests {
     fn test_endpoint_option_expiration_chain() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/expirationchain",
-            EndpointOptionExpirationChain::ExpirationChain.url()
+            EndpointOptionExpirationChain::ExpirationChain.url(DEFAULT_BASE_URL)
         );
     }
 
@@ -485,7 +487,7 @@ mod tests {
     fn test_endpoint_price_history() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/pricehistory",
-            EndpointPriceHistory::PriceHistory.url()
+            EndpointPriceHistory::PriceHistory.url(DEFAULT_BASE_URL)
         );
     }
 
@@ -496,7 +498,7 @@ mod tests {
             EndpointMover::Mover {
                 symbol_id: "ABC".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -504,7 +506,7 @@ mod tests {
     fn test_endpoint_market_hour() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/markets",
-            EndpointMarketHour::Markets.url()
+            EndpointMarketHour::Markets.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -512,7 +514,7 @@ mod tests {
             EndpointMarketHour::Market {
                 market_id: Market::Equity,
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 
@@ -520,7 +522,7 @@ mod tests {
     fn test_endpoint_instrument() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/instruments",
-            EndpointInstrument::Instruments.url()
+            EndpointInstrument::Instruments.url(DEFAULT_BASE_URL)
         );
 
         assert_eq!(
@@ -528,7 +530,7 @@ mod tests {
             EndpointInstrument::Instrutment {
                 cusip_id: "123456".to_string()
             }
-            .url()
+            .url(DEFAULT_BASE_URL)
         );
     }
 }
@@ -9,21 +9,52 @@ use super::parameter::{
     Projection, QuoteField, SortAttribute,
 };
 use crate::api::Error;
+use crate::error::{self, ApiError};
 use crate::model;
 
 use super::endpoints;
+use super::retry::{RetryPolicy, Sender};
 
+/// Turn a non-successful response into [`Error::RateLimited`] on `429 Too Many Requests`, [`Error::Response`], or [`Error::Api`] with the raw body if it isn't an [`model::ErrorResponse`].
 async fn process_error(rsp: Response) -> Result<Error, Error> {
-    let json = rsp.text().await?;
-    dbg!(&json);
-    let error_response: model::ErrorResponse = serde_json::from_str(&json)?;
-    Ok(Error::Response(error_response))
+    let status = rsp.status();
+    let url = rsp.url().to_string();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Ok(Error::RateLimited {
+            url,
+            retry_after: error::retry_after(rsp.headers()),
+        });
+    }
+    if status == StatusCode::UNAUTHORIZED {
+        return Ok(Error::Unauthorized {
+            reauth_required: false,
+        });
+    }
+
+    let body = rsp.text().await?;
+    Ok(match serde_json::from_str::<model::ErrorResponse>(&body) {
+        Ok(error) => Error::Response { url, status, error },
+        Err(_) => Error::Api(ApiError { url, status, body }),
+    })
+}
+
+/// Send the request and return the response body as raw JSON, without deserializing into the model.
+async fn send_raw(sender: &Sender, req: RequestBuilder) -> Result<serde_json::Value, Error> {
+    let rsp = sender.send(req).await?;
+
+    let status = rsp.status();
+    if status != StatusCode::OK {
+        return Err(process_error(rsp).await?);
+    }
+
+    error::deserialize::<serde_json::Value>(rsp).await
 }
 
 /// Get Quotes by list of symbols.
 #[derive(Debug)]
 pub struct GetQuotesRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbols: Vec<String>,
 
@@ -47,14 +78,22 @@ impl GetQuotesRequest {
         endpoints::EndpointQuote::Quotes
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbols: Vec<String>) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbols: Vec<String>,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, symbols)
     }
 
     fn new_with(req: RequestBuilder, symbols: Vec<String>) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbols,
             fields: None,
             indicative: None,
@@ -81,6 +120,12 @@ impl GetQuotesRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbols", self.symbols.join(","))]);
         if let Some(x) = self.fields {
@@ -98,9 +143,16 @@ impl GetQuotesRequest {
         req
     }
 
+    /// Same as `send`, but return the raw JSON, e.g. to read a field not modeled yet or to debug a deserialization failure.
+    pub async fn send_raw(self) -> Result<serde_json::Value, Error> {
+        let sender = self.sender.clone();
+        send_raw(&sender, self.build()).await
+    }
+
     pub async fn send(self) -> Result<HashMap<String, model::QuoteResponse>, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -111,11 +163,10 @@ impl GetQuotesRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        let map = rsp.json::<model::QuoteResponseMap>().await?;
+        let map = error::deserialize::<model::QuoteResponseMap>(rsp).await?;
 
         if let Some(e) = map.errors {
             return Err(Error::Quote(e));
@@ -129,6 +180,7 @@ impl GetQuotesRequest {
 #[derive(Debug)]
 pub struct GetQuoteRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbol: String,
 
@@ -147,9 +199,14 @@ impl GetQuoteRequest {
         endpoints::EndpointQuote::Quote { symbol_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(symbol.clone()).url())
+            .get(Self::endpoint(symbol.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, symbol)
     }
@@ -157,6 +214,7 @@ impl GetQuoteRequest {
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbol,
             fields: None,
         }
@@ -174,6 +232,12 @@ impl GetQuoteRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -188,13 +252,20 @@ impl GetQuoteRequest {
         req
     }
 
+    /// Same as `send`, but return the raw JSON, e.g. to read a field not modeled yet or to debug a deserialization failure.
+    pub async fn send_raw(self) -> Result<serde_json::Value, Error> {
+        let sender = self.sender.clone();
+        send_raw(&sender, self.build()).await
+    }
+
     /// # Panics
     ///
     /// Will panic if no symbol found
     pub async fn send(self) -> Result<model::QuoteResponse, Error> {
         let symbol = self.symbol.clone();
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         //let json = rsp.text().await.unwrap();
         //dbg!(&json);
@@ -204,18 +275,19 @@ impl GetQuoteRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        let mut map = rsp.json::<model::QuoteResponseMap>().await?;
+        let mut map = error::deserialize::<model::QuoteResponseMap>(rsp).await?;
 
         if let Some(e) = map.errors {
             return Err(Error::Quote(e));
         }
 
-        let val = map.responses.remove(&symbol).expect("must exist");
-        Ok(val)
+        match map.responses.remove(&symbol).expect("must exist") {
+            model::QuoteResponse::Error(e) => Err(Error::Quote(e)),
+            val => Ok(val),
+        }
     }
 }
 
@@ -223,6 +295,7 @@ impl GetQuoteRequest {
 #[derive(Debug)]
 pub struct GetOptionChainsRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbol: String,
 
@@ -302,14 +375,22 @@ impl GetOptionChainsRequest {
         endpoints::EndpointOptionChain::Chains
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, symbol)
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbol,
             contract_type: None,
             strike_count: None,
@@ -355,6 +436,8 @@ impl GetOptionChainsRequest {
     ///
     /// `ANALYTICAL` allows the use of [`Self::volatility`], [`Self::underlying_price`], [`Self::interest_rate`], and [`Self::days_to_expiration`] params to calculate theoretical values.
     ///
+    /// Sending fails with [`Error::InvalidConfig`] if `ANALYTICAL` misses any of those params, or a spread strategy is combined with [`Self::strike_count`].
+    ///
     /// Available values : `SINGLE`, `ANALYTICAL`, `COVERED`, `VERTICAL`, `CALENDAR`, `STRANGLE`, `STRADDLE`, `BUTTERFLY`, `CONDOR`, `DIAGONAL`, `COLLAR`, `ROLL`
     pub fn strategy(&mut self, val: OptionChainStrategy) -> &mut Self {
         self.strategy = Some(val);
@@ -425,7 +508,9 @@ impl GetOptionChainsRequest {
         self
     }
 
-    /// Expiration month
+    /// Expiration month, e.g. to only get the front-month chain.
+    ///
+    /// Sending fails with [`Error::InvalidConfig`] if no day between [`Self::from_date`] and [`Self::to_date`] falls in this month.
     ///
     /// Available values : `JAN`, `FEB`, `MAR`, `APR`, `MAY`, `JUN`, `JUL`, `AUG`, `SEP`, `OCT`, `NOV`, `DEC`, `ALL`
     pub fn exp_month(&mut self, val: Month) -> &mut Self {
@@ -447,7 +532,78 @@ impl GetOptionChainsRequest {
         self
     }
 
-    fn build(self) -> RequestBuilder {
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
+    /// Reject parameter combinations which Schwab answers with a `400 Bad Request`.
+    fn validate(&self) -> Result<(), Error> {
+        match self.strategy {
+            Some(OptionChainStrategy::Analytical) => {
+                let missing = [
+                    ("volatility", self.volatility.is_none()),
+                    ("underlyingPrice", self.underlying_price.is_none()),
+                    ("interestRate", self.interest_rate.is_none()),
+                    ("daysToExpiration", self.days_to_expiration.is_none()),
+                ]
+                .into_iter()
+                .filter_map(|(name, is_none)| is_none.then_some(name))
+                .collect::<Vec<_>>();
+                if !missing.is_empty() {
+                    return Err(Error::InvalidConfig(format!(
+                        "ANALYTICAL strategy requires {}",
+                        missing.join(", ")
+                    )));
+                }
+            }
+            Some(
+                strategy @ (OptionChainStrategy::Vertical
+                | OptionChainStrategy::Calendar
+                | OptionChainStrategy::Strangle
+                | OptionChainStrategy::Straddle
+                | OptionChainStrategy::Butterfly
+                | OptionChainStrategy::Condor
+                | OptionChainStrategy::Diagonal
+                | OptionChainStrategy::Collar
+                | OptionChainStrategy::Roll),
+            ) if self.strike_count.is_some() => {
+                return Err(Error::InvalidConfig(format!(
+                    "{strategy:?} strategy conflicts with strikeCount"
+                )));
+            }
+            _ => {}
+        }
+
+        if let (Some(month), Some(from), Some(to)) = (
+            self.exp_month.and_then(Month::number),
+            self.from_date,
+            self.to_date,
+        ) {
+            use chrono::Datelike;
+
+            // walk the months touched by the range, a year at most before every month showed up
+            let covered = std::iter::successors(from.with_day(1), |first| {
+                first.checked_add_months(chrono::Months::new(1))
+            })
+            .take_while(|first| *first <= to)
+            .take(12)
+            .any(|first| first.month() == month);
+            if !covered {
+                return Err(Error::InvalidConfig(format!(
+                    "expMonth {:?} conflicts with fromDate {from} and toDate {to}",
+                    self.exp_month.unwrap()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build(self) -> Result<RequestBuilder, Error> {
+        self.validate()?;
+
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.contract_type {
             req = req.query(&[("contractType", x)]);
@@ -498,12 +654,19 @@ impl GetOptionChainsRequest {
             req = req.query(&[("entitlement", x)]);
         }
 
-        req
+        Ok(req)
+    }
+
+    /// Same as `send`, but return the raw JSON, e.g. to read a field not modeled yet or to debug a deserialization failure.
+    pub async fn send_raw(self) -> Result<serde_json::Value, Error> {
+        let sender = self.sender.clone();
+        send_raw(&sender, self.build()?).await
     }
 
     pub async fn send(self) -> Result<model::OptionChain, Error> {
-        let req = self.build();
-        let rsp = req.send().await?;
+        let sender = self.sender.clone();
+        let req = self.build()?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -517,9 +680,7 @@ impl GetOptionChainsRequest {
             return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::OptionChain>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::OptionChain>(rsp).await
     }
 }
 
@@ -527,6 +688,7 @@ impl GetOptionChainsRequest {
 #[derive(Debug)]
 pub struct GetOptionExpirationChainRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbol: String,
 }
@@ -536,13 +698,30 @@ impl GetOptionExpirationChainRequest {
         endpoints::EndpointOptionExpirationChain::ExpirationChain
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req: RequestBuilder = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req: RequestBuilder = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, symbol)
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
-        Self { req, symbol }
+        Self {
+            req,
+            sender: Sender::default(),
+            symbol,
+        }
+    }
+
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -550,8 +729,9 @@ impl GetOptionExpirationChainRequest {
     }
 
     pub async fn send(self) -> Result<model::ExpirationChain, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -562,13 +742,10 @@ impl GetOptionExpirationChainRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::ExpirationChain>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::ExpirationChain>(rsp).await
     }
 }
 
@@ -576,6 +753,7 @@ impl GetOptionExpirationChainRequest {
 #[derive(Debug)]
 pub struct GetPriceHistoryRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbol: String,
 
@@ -647,14 +825,22 @@ impl GetPriceHistoryRequest {
         endpoints::EndpointPriceHistory::PriceHistory
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, symbol)
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbol,
             period_type: None,
             period: None,
@@ -751,6 +937,12 @@ impl GetPriceHistoryRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.period_type {
@@ -782,8 +974,9 @@ impl GetPriceHistoryRequest {
     }
 
     pub async fn send(self) -> Result<model::CandleList, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -794,13 +987,10 @@ impl GetPriceHistoryRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::CandleList>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::CandleList>(rsp).await
     }
 }
 
@@ -808,6 +998,7 @@ impl GetPriceHistoryRequest {
 #[derive(Debug)]
 pub struct GetMoversRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     /// Index Symbol
     ///
@@ -836,9 +1027,14 @@ impl GetMoversRequest {
         endpoints::EndpointMover::Mover { symbol_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(symbol.clone()).url())
+            .get(Self::endpoint(symbol.clone()).url(base_url))
             .bearer_auth(access_token);
 
         Self::new_with(req, symbol)
@@ -847,6 +1043,7 @@ impl GetMoversRequest {
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbol,
             sort: None,
             frequency: None,
@@ -873,6 +1070,12 @@ impl GetMoversRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.sort {
@@ -886,8 +1089,9 @@ impl GetMoversRequest {
     }
 
     pub async fn send(self) -> Result<model::Mover, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -898,13 +1102,10 @@ impl GetMoversRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Mover>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Mover>(rsp).await
     }
 }
 
@@ -912,6 +1113,7 @@ impl GetMoversRequest {
 #[derive(Debug)]
 pub struct GetMarketsRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     /// List of markets
     ///
@@ -930,8 +1132,15 @@ impl GetMarketsRequest {
         endpoints::EndpointMarketHour::Markets
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, markets: Vec<Market>) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        markets: Vec<Market>,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
 
         Self::new_with(req, markets)
     }
@@ -939,6 +1148,7 @@ impl GetMarketsRequest {
     fn new_with(req: RequestBuilder, markets: Vec<Market>) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             markets,
             date: None,
         }
@@ -952,6 +1162,12 @@ impl GetMarketsRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let markets: Vec<String> = self
             .markets
@@ -968,8 +1184,9 @@ impl GetMarketsRequest {
     }
 
     pub async fn send(self) -> Result<model::Markets, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -980,13 +1197,10 @@ impl GetMarketsRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Markets>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Markets>(rsp).await
     }
 }
 
@@ -994,6 +1208,7 @@ impl GetMarketsRequest {
 #[derive(Debug)]
 pub struct GetMarketRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     /// Available values : `equity`, `option`, `bond`, `future`, `forex`
     market_id: Market,
@@ -1010,9 +1225,14 @@ impl GetMarketRequest {
         endpoints::EndpointMarketHour::Market { market_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, market_id: Market) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        market_id: Market,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(market_id).url())
+            .get(Self::endpoint(market_id).url(base_url))
             .bearer_auth(access_token);
 
         Self::new_with(req, market_id)
@@ -1021,6 +1241,7 @@ impl GetMarketRequest {
     fn new_with(req: RequestBuilder, market_id: Market) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             market_id,
             date: None,
         }
@@ -1034,6 +1255,12 @@ impl GetMarketRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("market_id", self.market_id)]);
         if let Some(x) = self.date {
@@ -1044,8 +1271,9 @@ impl GetMarketRequest {
     }
 
     pub async fn send(self) -> Result<model::Markets, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1056,13 +1284,10 @@ impl GetMarketRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Markets>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Markets>(rsp).await
     }
 }
 
@@ -1070,6 +1295,7 @@ impl GetMarketRequest {
 #[derive(Debug)]
 pub struct GetInstrumentsRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     symbol: String,
 
@@ -1086,22 +1312,32 @@ impl GetInstrumentsRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         symbol: String,
         projection: Projection,
     ) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, symbol, projection)
     }
 
     fn new_with(req: RequestBuilder, symbol: String, projection: Projection) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             symbol,
             projection,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
             .query(&[("symbol", self.symbol)])
@@ -1109,8 +1345,9 @@ impl GetInstrumentsRequest {
     }
 
     pub async fn send(self) -> Result<model::Instruments, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1121,13 +1358,10 @@ impl GetInstrumentsRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Instruments>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Instruments>(rsp).await
     }
 }
 
@@ -1135,6 +1369,7 @@ impl GetInstrumentsRequest {
 #[derive(Debug)]
 pub struct GetInstrumentRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// cusip of a security
@@ -1146,15 +1381,30 @@ impl GetInstrumentRequest {
         endpoints::EndpointInstrument::Instrutment { cusip_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, cusip_id: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        cusip_id: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(cusip_id.clone()).url())
+            .get(Self::endpoint(cusip_id.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, cusip_id)
     }
 
     fn new_with(req: RequestBuilder, cusip_id: String) -> Self {
-        Self { req, cusip_id }
+        Self {
+            req,
+            sender: Sender::default(),
+            cusip_id,
+        }
+    }
+
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -1165,8 +1415,9 @@ impl GetInstrumentRequest {
     ///
     /// Will panic if no Instrument
     pub async fn send(self) -> Result<model::InstrumentResponse, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1177,14 +1428,10 @@ impl GetInstrumentRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
-            return Err(Error::Response(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        let mut data = rsp
-            .json::<model::Instruments>()
-            .await
-            .map_err(std::convert::Into::<Error>::into)?;
+        let mut data = error::deserialize::<model::Instruments>(rsp).await?;
 
         Ok(data.instruments.pop().expect("must exist"))
     }
@@ -1260,6 +1507,24 @@ mod tests {
         assert_eq!(result.len(), 17);
     }
 
+    #[test]
+    fn test_get_quotes_request_indicative() {
+        let client = Client::new();
+        let req =
+            || GetQuotesRequest::new_with(client.get("http://127.0.0.1"), vec!["SPY".to_string()]);
+
+        // left out by default, as if `false`
+        let url = req().build().build().unwrap().url().clone();
+        assert!(url.query_pairs().all(|(k, _)| k != "indicative"));
+
+        let mut indicative = req();
+        indicative.indicative(true);
+        let url = indicative.build().build().unwrap().url().clone();
+        assert!(url
+            .query_pairs()
+            .any(|(k, v)| k == "indicative" && v == "true"));
+    }
+
     #[tokio::test]
     async fn test_get_quotes_request_real() {
         // Request a new server from the pool
@@ -1534,7 +1799,7 @@ mod tests {
         let underlying_price = 4.4;
         let interest_rate = 5.5;
         let days_to_expiration = 2;
-        let exp_month = Month::Jan;
+        let exp_month = Month::Apr;
         let option_type = "option_type".to_string();
         let entitlement = Entitlement::PN;
 
@@ -1559,7 +1824,7 @@ mod tests {
                 Matcher::UrlEncoded("underlyingPrice".into(), underlying_price.to_string()),
                 Matcher::UrlEncoded("interestRate".into(), interest_rate.to_string()),
                 Matcher::UrlEncoded("daysToExpiration".into(), days_to_expiration.to_string()),
-                Matcher::UrlEncoded("expMonth".into(), "JAN".into()),
+                Matcher::UrlEncoded("expMonth".into(), "APR".into()),
                 Matcher::UrlEncoded("optionType".into(), option_type.clone()),
                 Matcher::UrlEncoded("entitlement".into(), "PN".into()),
             ]))
@@ -1640,6 +1905,180 @@ mod tests {
         assert_eq!(result.status, "SUCCESS");
     }
 
+    #[test]
+    fn test_get_option_chains_request_validate() {
+        let client = Client::new();
+        let req =
+            || GetOptionChainsRequest::new_with(client.get("http://127.0.0.1"), "AAPL".to_string());
+
+        let mut analytical = req();
+        analytical
+            .strategy(OptionChainStrategy::Analytical)
+            .volatility(0.3);
+        let Err(Error::InvalidConfig(msg)) = analytical.build() else {
+            panic!("should be invalid");
+        };
+        assert_eq!(
+            msg,
+            "ANALYTICAL strategy requires underlyingPrice, interestRate, daysToExpiration"
+        );
+
+        let mut analytical = req();
+        analytical
+            .strategy(OptionChainStrategy::Analytical)
+            .volatility(0.3)
+            .underlying_price(100.0)
+            .interest_rate(0.05)
+            .days_to_expiration(30);
+        assert!(analytical.build().is_ok());
+
+        let mut vertical = req();
+        vertical
+            .strategy(OptionChainStrategy::Vertical)
+            .strike_count(5);
+        assert!(matches!(vertical.build(), Err(Error::InvalidConfig(_))));
+
+        let mut single = req();
+        single.strategy(OptionChainStrategy::Single).strike_count(5);
+        assert!(single.build().is_ok());
+
+        let date = |m, d| chrono::NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let mut front_month = req();
+        front_month
+            .exp_month(Month::Feb)
+            .from_date(date(1, 20))
+            .to_date(date(3, 1));
+        assert!(front_month.build().is_ok());
+
+        let mut conflict = req();
+        conflict
+            .exp_month(Month::Jun)
+            .from_date(date(1, 20))
+            .to_date(date(3, 1));
+        let Err(Error::InvalidConfig(msg)) = conflict.build() else {
+            panic!("should be invalid");
+        };
+        assert_eq!(
+            msg,
+            "expMonth Jun conflicts with fromDate 2025-01-20 and toDate 2025-03-01"
+        );
+
+        let mut all = req();
+        all.exp_month(Month::All)
+            .from_date(date(1, 20))
+            .to_date(date(3, 1));
+        assert!(all.build().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_raw() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock_quotes = server
+            .mock("GET", "/quotes")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .create_async()
+            .await;
+        let mock_chains = server
+            .mock("GET", "/chains")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/OptionChain_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let client = Client::new();
+
+        let req = client.get(format!(
+            "{url}{}",
+            GetQuotesRequest::endpoint().url_endpoint()
+        ));
+        let raw = GetQuotesRequest::new_with(req, vec!["AAPL".to_string()])
+            .send_raw()
+            .await
+            .unwrap();
+        mock_quotes.assert_async().await;
+        assert!(raw.is_object());
+        // the raw JSON still deserializes into the model
+        serde_json::from_value::<model::QuoteResponseMap>(raw).unwrap();
+
+        let req = client.get(format!(
+            "{url}{}",
+            GetOptionChainsRequest::endpoint().url_endpoint()
+        ));
+        let raw = GetOptionChainsRequest::new_with(req, "AAPL".to_string())
+            .send_raw()
+            .await
+            .unwrap();
+        mock_chains.assert_async().await;
+        assert_eq!(raw["status"], "SUCCESS");
+
+        // errors are handled the same as `send`
+        server.reset();
+        let _mock = server
+            .mock("GET", "/chains")
+            .match_query(Matcher::Any)
+            .with_status(502)
+            .with_body("Bad Gateway")
+            .create_async()
+            .await;
+        let req = client.get(format!(
+            "{url}{}",
+            GetOptionChainsRequest::endpoint().url_endpoint()
+        ));
+        let result = GetOptionChainsRequest::new_with(req, "AAPL".to_string())
+            .send_raw()
+            .await;
+        assert!(matches!(result, Err(Error::Api(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deserialize_error() {
+        let mut server = mockito::Server::new_async().await;
+        let body = r#"{"symbol": "AAPL", "status": 1}"#;
+        let mock = server
+            .mock("GET", "/chains")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let req = Client::new().get(format!(
+            "{}{}",
+            server.url(),
+            GetOptionChainsRequest::endpoint().url_endpoint()
+        ));
+        let result = GetOptionChainsRequest::new_with(req, "AAPL".to_string())
+            .send()
+            .await;
+        mock.assert_async().await;
+
+        let Err(Error::Deserialize {
+            endpoint,
+            source,
+            body: raw,
+        }) = result
+        else {
+            panic!("should fail to deserialize: {result:?}");
+        };
+        assert!(endpoint.starts_with(&format!("{}/chains?", server.url())));
+        assert!(source.is_data());
+        assert_eq!(raw, body);
+    }
+
     #[tokio::test]
     async fn test_get_option_expiration_chain_request() {
         // Request a new server from the pool
@@ -1900,6 +2339,85 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_process_error() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/markets/equity")
+            .match_query(Matcher::Any)
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/ErrorResponse.json"
+            ))
+            .create_async()
+            .await;
+        let mock_text = server
+            .mock("GET", "/markets/option")
+            .match_query(Matcher::Any)
+            .with_status(502)
+            .with_body("Bad Gateway")
+            .create_async()
+            .await;
+
+        let client = Client::new();
+
+        let req = client.get(format!(
+            "{url}{}",
+            GetMarketRequest::endpoint(Market::Equity).url_endpoint()
+        ));
+        let result = GetMarketRequest::new_with(req, Market::Equity).send().await;
+        mock.assert_async().await;
+        let Err(Error::Response {
+            url: error_url,
+            status,
+            error,
+        }) = result
+        else {
+            panic!("should be Error::Response: {result:?}");
+        };
+        assert!(error_url.starts_with(&format!("{url}/markets/equity")));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.errors.len(), 6);
+
+        let req = client.get(format!(
+            "{url}{}",
+            GetMarketRequest::endpoint(Market::Option).url_endpoint()
+        ));
+        let result = GetMarketRequest::new_with(req, Market::Option).send().await;
+        mock_text.assert_async().await;
+        let Err(Error::Api(e)) = result else {
+            panic!("should be Error::Api: {result:?}");
+        };
+        assert_eq!(e.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(e.body, "Bad Gateway");
+        assert!(e.url.starts_with(&format!("{url}/markets/option")));
+
+        let mock_rate_limited = server
+            .mock("GET", "/markets/bond")
+            .match_query(Matcher::Any)
+            .with_status(429)
+            .with_header("retry-after", "3")
+            .create_async()
+            .await;
+        let req = client.get(format!(
+            "{url}{}",
+            GetMarketRequest::endpoint(Market::Bond).url_endpoint()
+        ));
+        let result = GetMarketRequest::new_with(req, Market::Bond).send().await;
+        mock_rate_limited.assert_async().await;
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited {
+                retry_after: Some(x),
+                ..
+            }) if x == std::time::Duration::from_secs(3)
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_market_request() {
         // Request a new server from the pool
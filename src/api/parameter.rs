@@ -2,10 +2,13 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::model::market_data::option_chain::PutCall;
+use crate::Error;
+
 /// Field
 ///
 /// possible root nodes are `quote`, `fundamental`, `extended`, `reference`, `regular`.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum QuoteField {
     Quote,
@@ -21,7 +24,7 @@ pub enum QuoteField {
 /// Contract Type
 ///
 /// Available values : `CALL`, `PUT`, `ALL`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum ContractType {
     Call,
@@ -32,7 +35,7 @@ pub enum ContractType {
 /// Option Chain strategy
 ///
 /// Available values : `SINGLE`, `ANALYTICAL`, `COVERED`, `VERTICAL`, `CALENDAR`, `STRANGLE`, `STRADDLE`, `BUTTERFLY`, `CONDOR`, `DIAGONAL`, `COLLAR`, `ROLL`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum OptionChainStrategy {
     Single,
@@ -52,7 +55,7 @@ pub enum OptionChainStrategy {
 /// Expiration month
 ///
 /// Available values : `JAN`, `FEB`, `MAR`, `APR`, `MAY`, `JUN`, `JUL`, `AUG`, `SEP`, `OCT`, `NOV`, `DEC`, `ALL`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Month {
     Jan,
@@ -70,10 +73,31 @@ pub enum Month {
     All,
 }
 
+impl Month {
+    /// Calendar month number `1..=12`, `None` for [`Month::All`].
+    pub(crate) fn number(self) -> Option<u32> {
+        match self {
+            Month::Jan => Some(1),
+            Month::Feb => Some(2),
+            Month::Mar => Some(3),
+            Month::Apr => Some(4),
+            Month::May => Some(5),
+            Month::Jun => Some(6),
+            Month::Jul => Some(7),
+            Month::Aug => Some(8),
+            Month::Sep => Some(9),
+            Month::Oct => Some(10),
+            Month::Nov => Some(11),
+            Month::Dec => Some(12),
+            Month::All => None,
+        }
+    }
+}
+
 /// Applicable only if its retail token, entitlement of client PP-PayingPro, NP-NonPro and PN-NonPayingPro
 ///
 /// Available values : `PN`, `NP`, `PP`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Entitlement {
     PN,
@@ -84,7 +108,7 @@ pub enum Entitlement {
 /// The chart period being requested.
 ///
 /// Available values : `day`, `month`, `year`, `ytd`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PeriodType {
     Day,
@@ -96,7 +120,7 @@ pub enum PeriodType {
 /// The time frequency.
 ///
 /// Available values : `minute`, `daily`, `weekly`, `monthly`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FrequencyType {
     Minute,
@@ -108,7 +132,7 @@ pub enum FrequencyType {
 /// Sort by a particular attribute
 ///
 /// Available values : `VOLUME`, `TRADES`, `PERCENT_CHANGE_UP`, `PERCENT_CHANGE_DOWN`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SortAttribute {
     Volume,
@@ -120,7 +144,7 @@ pub enum SortAttribute {
 /// Market
 ///
 /// Available values : `equity`, `option`, `bond`, `future`, `forex`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Market {
     Equity,
@@ -133,7 +157,7 @@ pub enum Market {
 /// search by
 ///
 /// Available values : `symbol-search`, `symbol-regex`, `desc-search`, `desc-regex`, `search`, `fundamental`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Projection {
     SymbolSearch,
@@ -147,7 +171,7 @@ pub enum Projection {
 /// Specifies that only orders of this status should be returned.
 ///
 /// Available values : `AWAITING_PARENT_ORDER`, `AWAITING_CONDITION`, `AWAITING_STOP_CONDITION`, `AWAITING_MANUAL_REVIEW`, `ACCEPTED`, `AWAITING_UR_OUT`, `PENDING_ACTIVATION`, `QUEUED`, `WORKING`, `REJECTED`, `PENDING_CANCEL`, `CANCELED`, `PENDING_REPLACE`, `REPLACED`, `FILLED`, `EXPIRED`, `NEW`, `AWAITING_RELEASE_TIME`, `PENDING_ACKNOWLEDGEMENT`, `PENDING_RECALL`, `UNKNOWN`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Status {
     AwaitingParentOrder,
@@ -176,7 +200,7 @@ pub enum Status {
 /// Specifies that only transactions of this status should be returned.
 ///
 /// Available values : `TRADE`, `RECEIVE_AND_DELIVER`, `DIVIDEND_OR_INTEREST`, `ACH_RECEIPT`, `ACH_DISBURSEMENT`, `CASH_RECEIPT`, `CASH_DISBURSEMENT`, `ELECTRONIC_FUND`, `WIRE_OUT`, `WIRE_IN`, `JOURNAL`, `MEMORANDUM`, `MARGIN_CALL`, `MONEY_MARKET`, `SMA_ADJUSTMENT`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionType {
     Trade,
@@ -195,3 +219,329 @@ pub enum TransactionType {
     MoneyMarket,
     SmaAdjustment,
 }
+
+/// This allows one to determine which fields they want returned.
+///
+/// Available values : `positions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountField {
+    Positions,
+}
+
+/// Symbol of a security
+///
+/// [`Symbol::new`] validates it against Schwab's symbol rules:
+/// - non-empty and uppercased, e.g. `AAPL`, `BRK.B`, `$SPX`
+/// - futures start with `/`, e.g. `/ESZ24`
+/// - options follow the OCC format, the root padded with spaces to 6 characters, then `YYMMDD`, `C` or `P`, and the strike price multiplied by 1000 in 8 digits, e.g. `AAPL  240517C00170000`
+///
+/// Converting from a `String` or `&str` does not validate, which is deferred to the API call taking the symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Symbol(String);
+
+impl Symbol {
+    pub fn new(symbol: impl Into<String>) -> Result<Self, Error> {
+        let symbol = Self(symbol.into());
+        symbol.validate()?;
+        Ok(symbol)
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let s = self.0.as_str();
+        let error = |reason: &str| Err(Error::Symbol(format!("{s:?} {reason}")));
+
+        if s.trim().is_empty() {
+            return error("is empty");
+        }
+        if s.chars().any(char::is_lowercase) {
+            return error("should be uppercased");
+        }
+
+        if s.contains(' ') {
+            return Self::validate_option(s).map_or_else(
+                || error("is not a valid option symbol, e.g. `AAPL  240517C00170000`"),
+                |()| Ok(()),
+            );
+        }
+
+        if let Some(root) = s.strip_prefix('/') {
+            if root.is_empty() || !root.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return error("is not a valid future symbol, e.g. `/ESZ24`");
+            }
+            return Ok(());
+        }
+
+        if !s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '/' | '$' | '^' | '-' | '_'))
+        {
+            return error("contains invalid characters");
+        }
+
+        Ok(())
+    }
+
+    fn validate_option(s: &str) -> Option<()> {
+        OptionSymbol::parse(s).ok().map(|_| ())
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(value: Symbol) -> Self {
+        value.0
+    }
+}
+
+/// Option symbol in the 21 characters OSI format, e.g. `AAPL  240517C00170000`
+///
+/// The root padded with spaces to 6 characters, then `YYMMDD`, `C` or `P`, and the strike price multiplied by 1000 in 8 digits.
+/// [`std::fmt::Display`] builds the canonical symbol back, so it is only built by [`Self::new`] or [`Self::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionSymbol {
+    underlying: String,
+    expiration: chrono::NaiveDate,
+    put_call: PutCall,
+    strike: f64,
+}
+
+impl OptionSymbol {
+    /// Fails with [`Error::Symbol`] if it doesn't fit the OSI format,
+    /// i.e. `underlying` isn't 1 to 6 uppercased characters or `strike` isn't within `0..100000`.
+    pub fn new(
+        underlying: impl Into<String>,
+        expiration: chrono::NaiveDate,
+        put_call: PutCall,
+        strike: f64,
+    ) -> Result<Self, Error> {
+        let underlying = underlying.into();
+        if !Self::is_valid_root(&underlying) {
+            return Err(Error::Symbol(format!(
+                "{underlying:?} is not a valid option root, 1 to 6 uppercased characters"
+            )));
+        }
+        if !(0.0..100_000.0).contains(&strike) || (strike * 1000.0).round() >= 100_000_000.0 {
+            return Err(Error::Symbol(format!(
+                "strike {strike} is out of the OSI range 0 to 99999.999"
+            )));
+        }
+
+        Ok(Self {
+            underlying,
+            expiration,
+            put_call,
+            strike,
+        })
+    }
+
+    fn is_valid_root(root: &str) -> bool {
+        (1..=6).contains(&root.len())
+            && root
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || matches!(c, '.' | '$'))
+    }
+
+    /// The option root, e.g. `SPXW`
+    #[must_use]
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    #[must_use]
+    pub fn expiration(&self) -> chrono::NaiveDate {
+        self.expiration
+    }
+
+    #[must_use]
+    pub fn put_call(&self) -> PutCall {
+        self.put_call
+    }
+
+    #[must_use]
+    pub fn strike(&self) -> f64 {
+        self.strike
+    }
+
+    pub fn parse(symbol: &str) -> Result<Self, Error> {
+        let error = || {
+            Error::Symbol(format!(
+                "{symbol:?} is not a valid option symbol, e.g. `AAPL  240517C00170000`"
+            ))
+        };
+
+        if symbol.len() != 21 || !symbol.is_ascii() {
+            return Err(error());
+        }
+        let (root, rest) = symbol.split_at(6);
+        let root = root.trim_end();
+        let (date, rest) = rest.split_at(6);
+        let (put_call, strike) = rest.split_at(1);
+
+        if !Self::is_valid_root(root)
+            || !date.chars().all(|c| c.is_ascii_digit())
+            || !strike.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+        let expiration = chrono::NaiveDate::parse_from_str(date, "%y%m%d").map_err(|_| error())?;
+        let put_call = match put_call {
+            "C" => PutCall::Call,
+            "P" => PutCall::Put,
+            _ => return Err(error()),
+        };
+        let strike = strike.parse::<u32>().map_err(|_| error())?;
+
+        Ok(Self {
+            underlying: root.to_string(),
+            expiration,
+            put_call,
+            strike: f64::from(strike) / 1000.0,
+        })
+    }
+}
+
+impl std::str::FromStr for OptionSymbol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let put_call = match self.put_call {
+            PutCall::Call => 'C',
+            PutCall::Put => 'P',
+        };
+        write!(
+            f,
+            "{:<6}{}{put_call}{:08.0}",
+            self.underlying,
+            self.expiration.format("%y%m%d"),
+            self.strike * 1000.0
+        )
+    }
+}
+
+impl From<OptionSymbol> for Symbol {
+    fn from(value: OptionSymbol) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol() {
+        for s in [
+            "AAPL",
+            "BRK.B",
+            "$SPX",
+            "/ESZ24",
+            "AAPL  240517C00170000",
+            "SPXW  240517P05000000",
+        ] {
+            assert_eq!(Symbol::new(s).unwrap().as_str(), s);
+        }
+
+        for s in [
+            "",
+            " ",
+            "aapl",
+            "AA PL",
+            "AAPL 240517C00170000",
+            "AAPL  240517X00170000",
+            "AAPL  24051AC00170000",
+            "/",
+            "/ES.Z",
+            "AAPL!",
+        ] {
+            assert!(matches!(Symbol::new(s), Err(Error::Symbol(_))), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_option_symbol() {
+        let expiration = chrono::NaiveDate::from_ymd_opt(2024, 5, 17).unwrap();
+        let symbol = OptionSymbol::parse("AAPL  240517C00170000").unwrap();
+        assert_eq!(
+            symbol,
+            OptionSymbol::new("AAPL", expiration, PutCall::Call, 170.0).unwrap()
+        );
+        assert_eq!(symbol.to_string(), "AAPL  240517C00170000");
+
+        let symbol: OptionSymbol = "SPXW  240517P05012500".parse().unwrap();
+        assert_eq!(symbol.underlying(), "SPXW");
+        assert_eq!(symbol.put_call(), PutCall::Put);
+        assert!((symbol.strike() - 5012.5).abs() < f64::EPSILON);
+        assert_eq!(Symbol::from(symbol).as_str(), "SPXW  240517P05012500");
+
+        for s in [
+            "AAPL 240517C00170000",
+            "AAPL  240517X00170000",
+            "AAPL  240231C00170000",
+            "      240517C00170000",
+            "AAPL  240517C0017000A",
+            "AAPL",
+        ] {
+            assert!(
+                matches!(OptionSymbol::parse(s), Err(Error::Symbol(_))),
+                "{s:?}"
+            );
+        }
+
+        // would not fit the 21 characters
+        for (root, strike) in [
+            ("GOOGLEX", 170.0),
+            ("", 170.0),
+            ("aapl", 170.0),
+            ("AAPL", 100_000.0),
+            ("AAPL", 99_999.999_9),
+            ("AAPL", -1.0),
+            ("AAPL", f64::NAN),
+        ] {
+            assert!(
+                matches!(
+                    OptionSymbol::new(root, expiration, PutCall::Call, strike),
+                    Err(Error::Symbol(_))
+                ),
+                "{root:?} {strike}"
+            );
+        }
+        let symbol = OptionSymbol::new("SPXW", expiration, PutCall::Put, 99_999.999).unwrap();
+        assert_eq!(symbol.to_string(), "SPXW  240517P99999999");
+    }
+}
@@ -0,0 +1,325 @@
+//! Retry with exponential backoff for transient failures, and once more after refreshing the access token on `401 Unauthorized`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{header, RequestBuilder, Response, StatusCode};
+
+use crate::error::{self, Error};
+use crate::token::Tokener;
+
+/// Retry policy for transient failures
+///
+/// Retries on `429 Too Many Requests`, `500 Internal Server Error`, `502 Bad Gateway`, `503 Service Unavailable`, connection errors and timeouts.
+///
+/// The delay doubles on every attempt, starting from `base_delay` and capped at `max_delay`.
+/// A `Retry-After` header is honored when present, but capped at `max_delay` as well.
+///
+/// The default policy does not retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The max number of retries after the first attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry.
+    pub base_delay: Duration,
+    /// The upper bound of the delay between retries.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    #[must_use]
+    pub fn new(max_retries: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    /// Never retry
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// The delay before the given retry, starting from 0.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+
+    fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+        )
+    }
+
+    fn is_retryable_error(e: &reqwest::Error) -> bool {
+        e.is_connect() || e.is_timeout()
+    }
+
+    /// Send the request, retrying according to this policy.
+    ///
+    /// The last response is returned as is once retries are exhausted, so the caller can handle its status.
+    pub(crate) async fn send(&self, req: RequestBuilder) -> Result<Response, Error> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            let span = Self::span(&req);
+            self.send_with_retry(req).instrument(span).await
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.send_with_retry(req).await
+    }
+
+    #[cfg(feature = "tracing")]
+    fn span(req: &RequestBuilder) -> tracing::Span {
+        if let Some(Ok(x)) = req.try_clone().map(RequestBuilder::build) {
+            tracing::debug_span!("request", method = %x.method(), url = %x.url())
+        } else {
+            tracing::debug_span!("request")
+        }
+    }
+
+    async fn send_with_retry(&self, req: RequestBuilder) -> Result<Response, Error> {
+        let mut retry = 0;
+        loop {
+            let Some(attempt) = (retry < self.max_retries)
+                .then(|| req.try_clone())
+                .flatten()
+            else {
+                // no more retries, or the body can't be cloned
+                return Self::checked(req.send().await);
+            };
+
+            let delay = match attempt.send().await {
+                Ok(rsp) if Self::is_retryable_status(rsp.status()) => {
+                    let delay = error::retry_after(rsp.headers())
+                        .map_or_else(|| self.backoff(retry), |x| x.min(self.max_delay));
+                    warn!(
+                        "retry {} in {delay:?} after status {}",
+                        retry + 1,
+                        rsp.status()
+                    );
+                    delay
+                }
+                Err(e) if Self::is_retryable_error(&e) => {
+                    let delay = self.backoff(retry);
+                    warn!("retry {} in {delay:?} after error: {e}", retry + 1);
+                    delay
+                }
+                result => return Self::checked(result),
+            };
+
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+
+    fn checked(result: reqwest::Result<Response>) -> Result<Response, Error> {
+        match &result {
+            Ok(rsp) => debug!("status {}", rsp.status()),
+            Err(e) => debug!("error: {e}"),
+        }
+        result.map_err(std::convert::Into::into)
+    }
+}
+
+type RefreshFuture = Pin<Box<dyn Future<Output = Result<String, Error>> + Send>>;
+
+/// Get a new access token without user interaction, see [`Tokener::refresh_access_token`].
+#[derive(Clone)]
+pub(crate) struct Refresher(Arc<dyn Fn() -> RefreshFuture + Send + Sync>);
+
+impl std::fmt::Debug for Refresher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Refresher")
+    }
+}
+
+impl Refresher {
+    pub(crate) fn new<T: Tokener>(tokener: Arc<T>) -> Self {
+        Self(Arc::new(move || {
+            let tokener = tokener.clone();
+            Box::pin(async move {
+                tokener.refresh_access_token().await?;
+                tokener.get_access_token().await
+            })
+        }))
+    }
+}
+
+/// How a request is sent: with the client headers, retried by the [`RetryPolicy`], and sent once more with a refreshed access token on `401 Unauthorized`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Sender {
+    pub(crate) retry_policy: RetryPolicy,
+    /// Set by [`crate::Api`], requests built by hand are not refreshed.
+    pub(crate) refresher: Option<Refresher>,
+    /// Added to every request, see [`crate::Api::client_headers`].
+    pub(crate) headers: header::HeaderMap,
+}
+
+impl Sender {
+    /// Send the request by [`RetryPolicy::send`].
+    ///
+    /// On `401 Unauthorized`, the access token is refreshed and the request is sent once more.
+    /// Fails with [`Error::Unauthorized`] requiring authorization if the refresh fails, e.g. when the refresh token was revoked.
+    pub(crate) async fn send(&self, req: RequestBuilder) -> Result<Response, Error> {
+        let req = req.headers(self.headers.clone());
+        let Some((refresher, retry)) = self.refresher.as_ref().zip(req.try_clone()) else {
+            // no refresher, or the body can't be cloned
+            return self.retry_policy.send(req).await;
+        };
+
+        let rsp = self.retry_policy.send(req).await?;
+        if rsp.status() != StatusCode::UNAUTHORIZED {
+            return Ok(rsp);
+        }
+
+        debug!("unauthorized, refreshing the access token");
+        let access_token = (refresher.0)().await?;
+        let (client, req) = retry.build_split();
+        let mut req = req?;
+        req.headers_mut().remove(header::AUTHORIZATION);
+        let req = RequestBuilder::from_parts(client, req).bearer_auth(access_token);
+        self.retry_policy.send(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::Client;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(500));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock_unavailable = server
+            .mock("GET", "/retry")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_rate_limited = server
+            .mock("GET", "/retry")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let policy = RetryPolicy::new(2, Duration::from_millis(1), Duration::from_millis(10));
+
+        // exhausted retries return the last response
+        let rsp = policy
+            .send(client.get(format!("{url}/retry")))
+            .await
+            .unwrap();
+        assert_eq!(rsp.status(), StatusCode::TOO_MANY_REQUESTS);
+        mock_unavailable.assert_async().await;
+        mock_rate_limited.assert_async().await;
+
+        // no retry by default
+        server.reset();
+        let mock = server
+            .mock("GET", "/retry")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = RetryPolicy::default()
+            .send(client.get(format!("{url}/retry")))
+            .await
+            .unwrap();
+        assert_eq!(rsp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_retry_after_capped() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock_rate_limited = server
+            .mock("GET", "/retry")
+            .with_status(429)
+            .with_header("retry-after", "3600")
+            .expect(1)
+            .create_async()
+            .await;
+        let mock_ok = server
+            .mock("GET", "/retry")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let policy = RetryPolicy::new(1, Duration::from_millis(1), Duration::from_millis(10));
+        let rsp = tokio::time::timeout(
+            Duration::from_secs(5),
+            policy.send(Client::new().get(format!("{url}/retry"))),
+        )
+        .await
+        .expect("Retry-After should be capped at max_delay")
+        .unwrap();
+        assert_eq!(rsp.status(), StatusCode::OK);
+        mock_rate_limited.assert_async().await;
+        mock_ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout() {
+        // accept connections but never respond
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let client = crate::api::client_builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let result = RetryPolicy::default()
+            .send(client.get(format!("http://{addr}/timeout")))
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+
+        server.abort();
+    }
+}
@@ -1,17 +1,59 @@
 //! APIs to access Account Balances & Positions, to perform trading activities
 //! [API Documentation](https://developer.schwab.com/products/trader-api--individual/details/specifications/Retail%20Trader%20API%20Production)
 
-use reqwest::{Client, RequestBuilder, StatusCode};
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
 
 use super::endpoints;
-use super::parameter::{Status, TransactionType};
+use super::parameter::{AccountField, Status, TransactionType};
+use super::retry::{RetryPolicy, Sender};
 use crate::api::Error;
+use crate::error::{self, ApiError};
 use crate::model;
 
+/// Turn a non-successful response into [`Error::RateLimited`] on `429 Too Many Requests`, [`Error::Service`], or [`Error::Api`] with the raw body if it isn't a [`model::ServiceError`].
+async fn process_error(rsp: Response) -> Result<Error, Error> {
+    let status = rsp.status();
+    let url = rsp.url().to_string();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Ok(Error::RateLimited {
+            url,
+            retry_after: error::retry_after(rsp.headers()),
+        });
+    }
+    if status == StatusCode::UNAUTHORIZED {
+        return Ok(Error::Unauthorized {
+            reauth_required: false,
+        });
+    }
+
+    let body = rsp.text().await?;
+    Ok(match serde_json::from_str::<model::ServiceError>(&body) {
+        Ok(error) => Error::Service { url, status, error },
+        Err(_) => Error::Api(ApiError { url, status, body }),
+    })
+}
+
+/// Extract the order ID from the `Location` header, e.g. `.../accounts/{accountNumber}/orders/{orderId}`
+fn order_id_from_location(rsp: &Response) -> Result<i64, Error> {
+    let location = rsp
+        .headers()
+        .get(header::LOCATION)
+        .ok_or_else(|| Error::Location("missing Location header".to_string()))?
+        .to_str()
+        .map_err(|e| Error::Location(e.to_string()))?;
+
+    location
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| Error::Location(format!("no order ID in {location}")))
+}
+
 /// Get list of account numbers and their encrypted values
 #[derive(Debug)]
 pub struct GetAccountNumbersRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 }
 
 impl GetAccountNumbersRequest {
@@ -19,13 +61,24 @@ impl GetAccountNumbersRequest {
         endpoints::EndpointAccount::AccountNumbers
     }
 
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(client: &Client, base_url: &str, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req)
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req }
+        Self {
+            req,
+            sender: Sender::default(),
+        }
+    }
+
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -33,18 +86,16 @@ impl GetAccountNumbersRequest {
     }
 
     pub async fn send(self) -> Result<model::AccountNumbers, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::AccountNumbers>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::AccountNumbers>(rsp).await
     }
 }
 
@@ -52,15 +103,12 @@ impl GetAccountNumbersRequest {
 #[derive(Debug)]
 pub struct GetAccountsRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     /// This allows one to determine which fields they want returned.
     ///
-    /// Possible value in this String can be: `positions`
-    ///
-    /// Example:
-    ///
-    /// fields=`positions`
-    fields: Option<String>,
+    /// Possible value can be: `positions`
+    fields: Option<AccountField>,
 }
 
 impl GetAccountsRequest {
@@ -68,27 +116,35 @@ impl GetAccountsRequest {
         endpoints::EndpointAccount::Accounts
     }
 
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(client: &Client, base_url: &str, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req)
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req, fields: None }
+        Self {
+            req,
+            sender: Sender::default(),
+            fields: None,
+        }
     }
 
     /// This allows one to determine which fields they want returned.
     ///
-    /// Possible value in this String can be: `positions`
-    ///
-    /// Example:
-    ///
-    /// fields=`positions`
-    pub fn fields(&mut self, val: String) -> &mut Self {
+    /// Possible value can be: `positions`
+    pub fn fields(&mut self, val: AccountField) -> &mut Self {
         self.fields = Some(val);
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -99,18 +155,16 @@ impl GetAccountsRequest {
     }
 
     pub async fn send(self) -> Result<model::Accounts, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Accounts>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Accounts>(rsp).await
     }
 }
 
@@ -118,6 +172,7 @@ impl GetAccountsRequest {
 #[derive(Debug)]
 pub struct GetAccountRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -125,12 +180,8 @@ pub struct GetAccountRequest {
 
     /// This allows one to determine which fields they want returned.
     ///
-    /// Possible value in this String can be: `positions`
-    ///
-    /// Example:
-    ///
-    /// fields=`positions`
-    fields: Option<String>,
+    /// Possible value can be: `positions`
+    fields: Option<AccountField>,
 }
 
 impl GetAccountRequest {
@@ -138,9 +189,14 @@ impl GetAccountRequest {
         endpoints::EndpointAccount::Account { account_number }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, account_number: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        base_url: &str,
+        access_token: String,
+        account_number: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number)
     }
@@ -148,6 +204,7 @@ impl GetAccountRequest {
     fn new_with(req: RequestBuilder, account_number: String) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             fields: None,
         }
@@ -155,16 +212,18 @@ impl GetAccountRequest {
 
     /// This allows one to determine which fields they want returned.
     ///
-    /// Possible value in this String can be: `positions`
-    ///
-    /// Example:
-    ///
-    /// fields=`positions`
-    pub fn fields(&mut self, val: String) -> &mut Self {
+    /// Possible value can be: `positions`
+    pub fn fields(&mut self, val: AccountField) -> &mut Self {
         self.fields = Some(val);
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -175,18 +234,16 @@ impl GetAccountRequest {
     }
 
     pub async fn send(self) -> Result<model::Account, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Account>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Account>(rsp).await
     }
 }
 
@@ -194,6 +251,7 @@ impl GetAccountRequest {
 #[derive(Debug)]
 pub struct GetAccountOrdersRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -230,13 +288,14 @@ impl GetAccountOrdersRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, from_entered_time, to_entered_time)
     }
@@ -249,6 +308,7 @@ impl GetAccountOrdersRequest {
     ) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             max_results: None,
             from_entered_time,
@@ -272,6 +332,12 @@ impl GetAccountOrdersRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             (
@@ -294,8 +360,9 @@ impl GetAccountOrdersRequest {
     }
 
     pub async fn send(self) -> Result<Vec<model::Order>, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -306,13 +373,10 @@ impl GetAccountOrdersRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<Vec<model::Order>>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<Vec<model::Order>>(rsp).await
     }
 }
 
@@ -320,6 +384,7 @@ impl GetAccountOrdersRequest {
 #[derive(Debug)]
 pub struct PostAccountOrderRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -335,12 +400,13 @@ impl PostAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         body: model::OrderRequest,
     ) -> Self {
         let req = client
-            .post(Self::endpoint(account_number.clone()).url())
+            .post(Self::endpoint(account_number.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, body)
     }
@@ -348,26 +414,34 @@ impl PostAccountOrderRequest {
     fn new_with(req: RequestBuilder, account_number: String, body: model::OrderRequest) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             body,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
-    pub async fn send(self) -> Result<(), Error> {
+    /// Returns the ID of the placed order.
+    pub async fn send(self) -> Result<i64, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::CREATED {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        Ok(())
+        order_id_from_location(&rsp)
     }
 }
 
@@ -375,6 +449,7 @@ impl PostAccountOrderRequest {
 #[derive(Debug)]
 pub struct GetAccountOrderRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -394,12 +469,13 @@ impl GetAccountOrderRequest {
     }
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         order_id: i64,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone(), order_id).url())
+            .get(Self::endpoint(account_number.clone(), order_id).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, order_id)
     }
@@ -407,18 +483,26 @@ impl GetAccountOrderRequest {
     fn new_with(req: RequestBuilder, account_number: String, order_id: i64) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             order_id,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
 
     pub async fn send(self) -> Result<model::Order, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -429,13 +513,10 @@ impl GetAccountOrderRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::Order>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::Order>(rsp).await
     }
 }
 
@@ -443,6 +524,7 @@ impl GetAccountOrderRequest {
 #[derive(Debug)]
 pub struct DeleteAccountOrderRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -463,12 +545,13 @@ impl DeleteAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         order_id: i64,
     ) -> Self {
         let req = client
-            .delete(Self::endpoint(account_number.clone(), order_id).url())
+            .delete(Self::endpoint(account_number.clone(), order_id).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, order_id)
     }
@@ -476,23 +559,37 @@ impl DeleteAccountOrderRequest {
     fn new_with(req: RequestBuilder, account_number: String, order_id: i64) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             order_id,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
 
+    /// A `400 Bad Request`, e.g. the order was already filled or canceled, is returned as [`Error::NotCancelable`].
     pub async fn send(self) -> Result<(), Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
-        if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+        if status == StatusCode::BAD_REQUEST {
+            return Err(match process_error(rsp).await? {
+                Error::Service { error, .. } => Error::NotCancelable(error),
+                e => e,
+            });
+        }
+        if status != StatusCode::OK && status != StatusCode::NO_CONTENT {
+            return Err(process_error(rsp).await?);
         }
 
         Ok(())
@@ -503,6 +600,7 @@ impl DeleteAccountOrderRequest {
 #[derive(Debug)]
 pub struct PutAccountOrderRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -525,13 +623,14 @@ impl PutAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         order_id: i64,
         body: model::OrderRequest,
     ) -> Self {
         let req = client
-            .put(Self::endpoint(account_number.clone(), order_id).url())
+            .put(Self::endpoint(account_number.clone(), order_id).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, order_id, body)
     }
@@ -544,27 +643,35 @@ impl PutAccountOrderRequest {
     ) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             order_id,
             body,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
-    pub async fn send(self) -> Result<(), Error> {
+    /// Returns the ID of the new order which replaces the old one.
+    pub async fn send(self) -> Result<i64, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::CREATED {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        Ok(())
+        order_id_from_location(&rsp)
     }
 }
 
@@ -572,6 +679,7 @@ impl PutAccountOrderRequest {
 #[derive(Debug)]
 pub struct GetAccountsOrdersRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     /// The max number of orders to retrieve.
     ///
@@ -605,11 +713,14 @@ impl GetAccountsOrdersRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
     ) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req, from_entered_time, to_entered_time)
     }
 
@@ -620,6 +731,7 @@ impl GetAccountsOrdersRequest {
     ) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             max_results: None,
             from_entered_time,
             to_entered_time,
@@ -643,6 +755,12 @@ impl GetAccountsOrdersRequest {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             (
@@ -665,18 +783,16 @@ impl GetAccountsOrdersRequest {
     }
 
     pub async fn send(self) -> Result<Vec<model::Order>, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<Vec<model::Order>>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<Vec<model::Order>>(rsp).await
     }
 }
 
@@ -684,12 +800,13 @@ impl GetAccountsOrdersRequest {
 #[derive(Debug)]
 pub struct PostAccountPreviewOrderRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
     account_number: String,
 
-    body: model::PreviewOrder,
+    body: model::OrderRequest,
 }
 
 impl PostAccountPreviewOrderRequest {
@@ -699,41 +816,48 @@ impl PostAccountPreviewOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
-        body: model::PreviewOrder,
+        body: model::OrderRequest,
     ) -> Self {
         let req = client
-            .post(Self::endpoint(account_number.clone()).url())
+            .post(Self::endpoint(account_number.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, body)
     }
 
-    fn new_with(req: RequestBuilder, account_number: String, body: model::PreviewOrder) -> Self {
+    fn new_with(req: RequestBuilder, account_number: String, body: model::OrderRequest) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             body,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
+    /// Returns the estimated balance, commission and fees, and the validation result without placing the order.
     pub async fn send(self) -> Result<model::PreviewOrder, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::PreviewOrder>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::PreviewOrder>(rsp).await
     }
 }
 
@@ -741,6 +865,7 @@ impl PostAccountPreviewOrderRequest {
 #[derive(Debug)]
 pub struct GetAccountTransactions {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -777,6 +902,7 @@ impl GetAccountTransactions {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         start_date: chrono::DateTime<chrono::Utc>,
@@ -784,7 +910,7 @@ impl GetAccountTransactions {
         types: TransactionType,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, start_date, end_date, types)
     }
@@ -798,6 +924,7 @@ impl GetAccountTransactions {
     ) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             start_date,
             end_date,
@@ -812,6 +939,12 @@ impl GetAccountTransactions {
         self
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             ("startDate", self.start_date.format("%+").to_string()),
@@ -826,8 +959,9 @@ impl GetAccountTransactions {
     }
 
     pub async fn send(self) -> Result<Vec<model::Transaction>, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -837,11 +971,10 @@ impl GetAccountTransactions {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json().await.map_err(std::convert::Into::into)
+        error::deserialize(rsp).await
     }
 }
 
@@ -849,6 +982,7 @@ impl GetAccountTransactions {
 #[derive(Debug)]
 pub struct GetAccountTransaction {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -869,12 +1003,13 @@ impl GetAccountTransaction {
 
     pub(crate) fn new(
         client: &Client,
+        base_url: &str,
         access_token: String,
         account_number: String,
         transaction_id: i64,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone(), transaction_id).url())
+            .get(Self::endpoint(account_number.clone(), transaction_id).url(base_url))
             .bearer_auth(access_token);
         Self::new_with(req, account_number, transaction_id)
     }
@@ -882,11 +1017,18 @@ impl GetAccountTransaction {
     fn new_with(req: RequestBuilder, account_number: String, transaction_id: i64) -> Self {
         Self {
             req,
+            sender: Sender::default(),
             account_number,
             transaction_id,
         }
     }
 
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
@@ -895,8 +1037,9 @@ impl GetAccountTransaction {
     ///
     /// Will panic if no transaction found
     pub async fn send(self) -> Result<model::Transaction, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -907,11 +1050,10 @@ impl GetAccountTransaction {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json().await.map_err(std::convert::Into::into)
+        error::deserialize(rsp).await
     }
 }
 
@@ -919,19 +1061,31 @@ impl GetAccountTransaction {
 #[derive(Debug)]
 pub struct GetUserPreferenceRequest {
     req: RequestBuilder,
+    pub(crate) sender: Sender,
 }
 
 impl GetUserPreferenceRequest {
     fn endpoint() -> endpoints::EndpointUserPreference {
         endpoints::EndpointUserPreference::UserPreference
     }
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(client: &Client, base_url: &str, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(base_url))
+            .bearer_auth(access_token);
         Self::new_with(req)
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req }
+        Self {
+            req,
+            sender: Sender::default(),
+        }
+    }
+
+    /// Retry policy for transient failures, see [`RetryPolicy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.sender.retry_policy = val;
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -939,8 +1093,9 @@ impl GetUserPreferenceRequest {
     }
 
     pub async fn send(self) -> Result<model::UserPreferences, Error> {
+        let sender = self.sender.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = sender.send(req).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -951,13 +1106,10 @@ impl GetUserPreferenceRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
-            return Err(Error::Service(error_response));
+            return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::UserPreferences>()
-            .await
-            .map_err(std::convert::Into::into)
+        error::deserialize::<model::UserPreferences>(rsp).await
     }
 }
 
@@ -1016,6 +1168,78 @@ mod tests {
         assert_eq!(result[0].account_number, "string");
     }
 
+    #[tokio::test]
+    async fn test_process_error() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/accounts/accountNumbers")
+            .with_status(401)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError_real2.json"
+            ))
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let req = client.get(format!(
+            "{url}{}",
+            GetAccountNumbersRequest::endpoint().url_endpoint()
+        ));
+        let result = GetAccountNumbersRequest::new_with(req).send().await;
+        mock.assert_async().await;
+        assert!(matches!(
+            result,
+            Err(Error::Unauthorized {
+                reauth_required: false
+            })
+        ));
+
+        mock.remove_async().await;
+        let mock = server
+            .mock("GET", "/accounts/accountNumbers")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError_real2.json"
+            ))
+            .create_async()
+            .await;
+        let req = client.get(format!(
+            "{url}{}",
+            GetAccountNumbersRequest::endpoint().url_endpoint()
+        ));
+        let result = GetAccountNumbersRequest::new_with(req).send().await;
+        mock.assert_async().await;
+        assert!(
+            matches!(result, Err(Error::Service { status, .. }) if status == StatusCode::BAD_REQUEST)
+        );
+
+        mock.remove_async().await;
+        let mock = server
+            .mock("GET", "/accounts/accountNumbers")
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .create_async()
+            .await;
+
+        let req = client.get(format!(
+            "{url}{}",
+            GetAccountNumbersRequest::endpoint().url_endpoint()
+        ));
+        let result = GetAccountNumbersRequest::new_with(req).send().await;
+        mock.assert_async().await;
+        let Err(Error::Api(e)) = result else {
+            panic!("should be Error::Api: {result:?}");
+        };
+        assert_eq!(e.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(e.body, "Service Unavailable");
+    }
+
     #[tokio::test]
     async fn test_get_accounts_request() {
         // Request a new server from the pool
@@ -1026,14 +1250,14 @@ mod tests {
         let url = server.url();
 
         // define parameter
-        let fields = "positions".to_string();
+        let fields = AccountField::Positions;
 
         // Create a mock
         let mock = server
             .mock("GET", "/accounts")
             .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
                 "fields".into(),
-                fields.to_string(),
+                "positions".into(),
             )]))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -1056,7 +1280,7 @@ mod tests {
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req.fields(fields);
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1080,14 +1304,14 @@ mod tests {
 
         // define parameter
         let account_number = "account_number".to_string();
-        let fields = "positions".to_string();
+        let fields = AccountField::Positions;
 
         // Create a mock
         let mock = server
             .mock("GET", "/accounts/account_number")
             .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
                 "fields".into(),
-                fields.to_string(),
+                "positions".into(),
             )]))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -1111,7 +1335,7 @@ mod tests {
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req.fields(fields);
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1225,6 +1449,10 @@ mod tests {
             .mock("POST", "/accounts/account_number/orders")
             .with_status(201)
             .with_header("content-type", "application/json")
+            .with_header(
+                "location",
+                "https://api.schwabapi.com/trader/v1/accounts/account_number/orders/1000",
+            )
             .match_body(mockito::Matcher::Json(
                 serde_json::to_value(body.clone()).unwrap(),
             ))
@@ -1249,7 +1477,7 @@ mod tests {
         dbg!(&req);
         let result = req.send().await;
         mock.assert_async().await;
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 1000);
     }
 
     #[tokio::test]
@@ -1341,6 +1569,37 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_delete_account_order_request_not_cancelable() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let account_number = "account_number".to_string();
+        let order_id = 123;
+
+        let mock = server
+            .mock("DELETE", "/accounts/account_number/orders/123")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let client = Client::new();
+        let req = client.delete(format!(
+            "{url}{}",
+            DeleteAccountOrderRequest::endpoint(account_number.clone(), order_id).url_endpoint()
+        ));
+
+        let req = DeleteAccountOrderRequest::new_with(req, account_number, order_id);
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::NotCancelable(_))));
+    }
+
     #[tokio::test]
     async fn test_put_account_order_request() {
         // Request a new server from the pool
@@ -1360,6 +1619,10 @@ mod tests {
             .mock("PUT", "/accounts/account_number/orders/123")
             .with_status(201)
             .with_header("content-type", "application/json")
+            .with_header(
+                "location",
+                "https://api.schwabapi.com/trader/v1/accounts/account_number/orders/124",
+            )
             .match_body(Matcher::Json(serde_json::to_value(body.clone()).unwrap()))
             .create_async()
             .await;
@@ -1384,7 +1647,7 @@ mod tests {
         dbg!(&req);
         let result = req.send().await;
         mock.assert_async().await;
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 124);
     }
 
     #[tokio::test]
@@ -1474,13 +1737,16 @@ mod tests {
 
         // define parameter
         let account_number = "account_number".to_string();
-        let body = model::PreviewOrder::default();
+        let body = model::OrderRequest::default();
 
         // Create a mock
         let mock = server
             .mock("POST", "/accounts/account_number/previewOrder")
             .with_status(200)
             .with_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(
+                serde_json::to_value(body.clone()).unwrap(),
+            ))
             .with_body_from_file(concat!(
                 env!("CARGO_MANIFEST_DIR"),
                 "/tests/model/Trader/PreviewOrder.json"
@@ -1543,7 +1809,7 @@ mod tests {
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("startDate".into(), start_date.format("%+").to_string()),
                 Matcher::UrlEncoded("endDate".into(), end_date.format("%+").to_string()),
-                Matcher::UrlEncoded("symbol".into(), symbol.to_string()),
+                Matcher::UrlEncoded("symbol".into(), symbol.clone()),
                 Matcher::UrlEncoded("types".into(), "RECEIVE_AND_DELIVER".into()),
             ]))
             .with_status(200)
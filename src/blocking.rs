@@ -0,0 +1,525 @@
+//! A blocking [`Api`], for code that doesn't run an async runtime.
+//!
+//! It mirrors [`crate::api::Api`], driving each request on an internal runtime.
+//! Like `reqwest::blocking`, it must not be used within an async runtime, or it will panic.
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//!
+//! use schwab_api::blocking::Api;
+//!
+//! let path = dirs::home_dir()
+//!     .expect("home dir")
+//!     .join(".credentials")
+//!     .join("Schwab-rust.json");
+//! let api = Api::with_token_checker(
+//!     path,
+//!     "your_app_key".to_string(),
+//!     "your_secret".to_string(),
+//!     "https://127.0.0.1:8080".to_string(),
+//!     PathBuf::from("your_certs_dir"),
+//! )
+//! .unwrap();
+//!
+//! let rsp = api.get_quote("VTI").unwrap().send().unwrap();
+//! println!("{:?}", rsp);
+//! ```
+
+use reqwest::Client;
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use tokio::runtime::Runtime;
+
+use crate::api::parameter::{Market, Projection, Symbol, TransactionType};
+use crate::api::retry::RetryPolicy;
+use crate::api::{self, market_data, trader};
+use crate::error::Error;
+use crate::model;
+use crate::token::{TokenChecker, Tokener};
+
+/// An async request of [`crate::api`], which [`Request`] can drive to completion.
+pub trait AsyncRequest {
+    type Output;
+
+    fn send(self) -> impl Future<Output = Result<Self::Output, Error>>;
+}
+
+macro_rules! impl_async_request {
+    ($($req:ty => $output:ty,)*) => {
+        $(
+            impl AsyncRequest for $req {
+                type Output = $output;
+
+                fn send(self) -> impl Future<Output = Result<Self::Output, Error>> {
+                    <$req>::send(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_async_request!(
+    market_data::GetQuotesRequest => HashMap<String, model::QuoteResponse>,
+    market_data::GetQuoteRequest => model::QuoteResponse,
+    market_data::GetOptionChainsRequest => model::OptionChain,
+    market_data::GetOptionExpirationChainRequest => model::ExpirationChain,
+    market_data::GetPriceHistoryRequest => model::CandleList,
+    market_data::GetMoversRequest => model::Mover,
+    market_data::GetMarketsRequest => model::Markets,
+    market_data::GetMarketRequest => model::Markets,
+    market_data::GetInstrumentsRequest => model::Instruments,
+    market_data::GetInstrumentRequest => model::InstrumentResponse,
+    trader::GetAccountNumbersRequest => model::AccountNumbers,
+    trader::GetAccountsRequest => model::Accounts,
+    trader::GetAccountRequest => model::Account,
+    trader::GetAccountOrdersRequest => Vec<model::Order>,
+    trader::PostAccountOrderRequest => i64,
+    trader::GetAccountOrderRequest => model::Order,
+    trader::DeleteAccountOrderRequest => (),
+    trader::PutAccountOrderRequest => i64,
+    trader::GetAccountsOrdersRequest => Vec<model::Order>,
+    trader::PostAccountPreviewOrderRequest => model::PreviewOrder,
+    trader::GetAccountTransactions => Vec<model::Transaction>,
+    trader::GetAccountTransaction => model::Transaction,
+    trader::GetUserPreferenceRequest => model::UserPreferences,
+);
+
+/// A request of [`crate::api`] bound to the runtime of the blocking [`Api`].
+///
+/// The setters of the wrapped request are available through `Deref`.
+#[derive(Debug)]
+pub struct Request<'a, R> {
+    req: R,
+    rt: &'a Runtime,
+}
+
+impl<R> Deref for Request<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        &self.req
+    }
+}
+
+impl<R> DerefMut for Request<'_, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.req
+    }
+}
+
+impl<R: AsyncRequest> Request<'_, R> {
+    pub fn send(self) -> Result<R::Output, Error> {
+        self.rt.block_on(self.req.send())
+    }
+}
+
+macro_rules! impl_send_raw {
+    ($($req:ty),*) => {
+        $(
+            impl Request<'_, $req> {
+                /// Same as `send`, but return the raw JSON.
+                pub fn send_raw(self) -> Result<serde_json::Value, Error> {
+                    self.rt.block_on(self.req.send_raw())
+                }
+            }
+        )*
+    };
+}
+
+impl_send_raw!(
+    market_data::GetQuotesRequest,
+    market_data::GetQuoteRequest,
+    market_data::GetOptionChainsRequest
+);
+
+/// Interacting with the Schwab API, blocking the current thread.
+#[derive(Debug)]
+pub struct Api<T: Tokener> {
+    inner: api::Api<T>,
+    rt: Runtime,
+}
+
+impl Api<TokenChecker> {
+    /// Create the API with a [`TokenChecker`], see [`TokenChecker::new`].
+    pub fn with_token_checker(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        certs_dir: PathBuf,
+    ) -> Result<Self, Error> {
+        let rt = runtime()?;
+        let client = api::client_builder().build()?;
+        let inner = rt.block_on(async {
+            let token_checker = TokenChecker::new(
+                path,
+                client_id,
+                secret,
+                redirect_url,
+                certs_dir,
+                client.clone(),
+            )
+            .await?;
+            api::Api::new(token_checker, client).await
+        })?;
+
+        Ok(Self { inner, rt })
+    }
+}
+
+impl<T: Tokener> Api<T> {
+    /// Create the API, see [`crate::api::Api::new`].
+    pub fn new(tokener: T, client: Client) -> Result<Self, Error> {
+        let rt = runtime()?;
+        let inner = rt.block_on(api::Api::new(tokener, client))?;
+
+        Ok(Self { inner, rt })
+    }
+
+    /// Create the API against another host, see [`crate::api::Api::with_base_url`].
+    pub fn with_base_url(
+        tokener: T,
+        client: Client,
+        base_url: impl Into<String>,
+    ) -> Result<Self, Error> {
+        let rt = runtime()?;
+        let inner = rt.block_on(api::Api::with_base_url(tokener, client, base_url))?;
+
+        Ok(Self { inner, rt })
+    }
+
+    fn request<R>(
+        &self,
+        req: impl Future<Output = Result<R, Error>>,
+    ) -> Result<Request<'_, R>, Error> {
+        let req = self.rt.block_on(req)?;
+        Ok(Request { req, rt: &self.rt })
+    }
+
+    /// See [`crate::api::Api::retry_policy`].
+    pub fn retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.inner.retry_policy(val);
+        self
+    }
+
+    /// See [`crate::api::Api::client_headers`].
+    pub fn client_headers(&mut self, val: reqwest::header::HeaderMap) -> &mut Self {
+        self.inner.client_headers(val);
+        self
+    }
+
+    pub fn get_quotes(
+        &self,
+        symbols: impl IntoIterator<Item = impl Into<Symbol>>,
+    ) -> Result<Request<'_, market_data::GetQuotesRequest>, Error> {
+        self.request(self.inner.get_quotes(symbols))
+    }
+
+    pub fn get_quotes_batched(
+        &self,
+        symbols: impl IntoIterator<Item = impl Into<Symbol>>,
+        chunk_size: Option<usize>,
+        max_concurrency: usize,
+    ) -> Result<api::QuotesBatch, Error> {
+        self.rt.block_on(
+            self.inner
+                .get_quotes_batched(symbols, chunk_size, max_concurrency),
+        )
+    }
+
+    pub fn get_quote(
+        &self,
+        symbol: impl Into<Symbol>,
+    ) -> Result<Request<'_, market_data::GetQuoteRequest>, Error> {
+        self.request(self.inner.get_quote(symbol))
+    }
+
+    pub fn get_option_chains(
+        &self,
+        symbol: impl Into<Symbol>,
+    ) -> Result<Request<'_, market_data::GetOptionChainsRequest>, Error> {
+        self.request(self.inner.get_option_chains(symbol))
+    }
+
+    pub fn get_option_expiration_chain(
+        &self,
+        symbol: impl Into<Symbol>,
+    ) -> Result<Request<'_, market_data::GetOptionExpirationChainRequest>, Error> {
+        self.request(self.inner.get_option_expiration_chain(symbol))
+    }
+
+    pub fn get_price_history(
+        &self,
+        symbol: String,
+    ) -> Result<Request<'_, market_data::GetPriceHistoryRequest>, Error> {
+        self.request(self.inner.get_price_history(symbol))
+    }
+
+    pub fn get_movers(
+        &self,
+        symbol: String,
+    ) -> Result<Request<'_, market_data::GetMoversRequest>, Error> {
+        self.request(self.inner.get_movers(symbol))
+    }
+
+    pub fn get_markets(
+        &self,
+        markets: Vec<Market>,
+    ) -> Result<Request<'_, market_data::GetMarketsRequest>, Error> {
+        self.request(self.inner.get_markets(markets))
+    }
+
+    pub fn get_market_hours_for_date(
+        &self,
+        markets: Vec<Market>,
+        date: chrono::NaiveDate,
+    ) -> Result<model::Markets, Error> {
+        self.rt
+            .block_on(self.inner.get_market_hours_for_date(markets, date))
+    }
+
+    pub fn get_market(
+        &self,
+        market_id: Market,
+    ) -> Result<Request<'_, market_data::GetMarketRequest>, Error> {
+        self.request(self.inner.get_market(market_id))
+    }
+
+    pub fn get_instruments(
+        &self,
+        symbol: String,
+        projection: Projection,
+    ) -> Result<Request<'_, market_data::GetInstrumentsRequest>, Error> {
+        self.request(self.inner.get_instruments(symbol, projection))
+    }
+
+    pub fn get_instrument(
+        &self,
+        cusip_id: String,
+    ) -> Result<Request<'_, market_data::GetInstrumentRequest>, Error> {
+        self.request(self.inner.get_instrument(cusip_id))
+    }
+
+    pub fn get_account_numbers(
+        &self,
+    ) -> Result<Request<'_, trader::GetAccountNumbersRequest>, Error> {
+        self.request(self.inner.get_account_numbers())
+    }
+
+    /// See [`crate::api::Api::account_hash`].
+    pub fn account_hash(&self, account_number: &str) -> Result<String, Error> {
+        self.rt.block_on(self.inner.account_hash(account_number))
+    }
+
+    /// See [`crate::api::Api::refresh_account_numbers`].
+    pub fn refresh_account_numbers(&self) -> Result<model::AccountNumbers, Error> {
+        self.rt.block_on(self.inner.refresh_account_numbers())
+    }
+
+    pub fn get_accounts(&self) -> Result<Request<'_, trader::GetAccountsRequest>, Error> {
+        self.request(self.inner.get_accounts())
+    }
+
+    pub fn get_account(
+        &self,
+        account_number: String,
+    ) -> Result<Request<'_, trader::GetAccountRequest>, Error> {
+        self.request(self.inner.get_account(account_number))
+    }
+
+    pub fn get_account_orders(
+        &self,
+        account_number: String,
+        from_entered_time: chrono::DateTime<chrono::Utc>,
+        to_entered_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Request<'_, trader::GetAccountOrdersRequest>, Error> {
+        self.request(self.inner.get_account_orders(
+            account_number,
+            from_entered_time,
+            to_entered_time,
+        ))
+    }
+
+    /// See [`crate::api::Api::get_all_account_orders`].
+    pub fn get_all_account_orders(
+        &self,
+        account_number: String,
+        from_entered_time: chrono::DateTime<chrono::Utc>,
+        to_entered_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<model::Order>, Error> {
+        self.rt.block_on(self.inner.get_all_account_orders(
+            account_number,
+            from_entered_time,
+            to_entered_time,
+        ))
+    }
+
+    pub fn post_account_order(
+        &self,
+        account_number: String,
+        body: model::OrderRequest,
+    ) -> Result<Request<'_, trader::PostAccountOrderRequest>, Error> {
+        self.request(self.inner.post_account_order(account_number, body))
+    }
+
+    pub fn get_account_order(
+        &self,
+        account_number: String,
+        order_id: i64,
+    ) -> Result<Request<'_, trader::GetAccountOrderRequest>, Error> {
+        self.request(self.inner.get_account_order(account_number, order_id))
+    }
+
+    pub fn wait_for_fill(
+        &self,
+        account_number: String,
+        order_id: i64,
+        poll_interval: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<model::Order, Error> {
+        self.rt.block_on(
+            self.inner
+                .wait_for_fill(account_number, order_id, poll_interval, timeout),
+        )
+    }
+
+    pub fn delete_account_order(
+        &self,
+        account_number: String,
+        order_id: i64,
+    ) -> Result<Request<'_, trader::DeleteAccountOrderRequest>, Error> {
+        self.request(self.inner.delete_account_order(account_number, order_id))
+    }
+
+    pub fn put_account_order(
+        &self,
+        account_number: String,
+        order_id: i64,
+        body: model::OrderRequest,
+    ) -> Result<Request<'_, trader::PutAccountOrderRequest>, Error> {
+        self.request(self.inner.put_account_order(account_number, order_id, body))
+    }
+
+    pub fn get_accounts_orders(
+        &self,
+        from_entered_time: chrono::DateTime<chrono::Utc>,
+        to_entered_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Request<'_, trader::GetAccountsOrdersRequest>, Error> {
+        self.request(
+            self.inner
+                .get_accounts_orders(from_entered_time, to_entered_time),
+        )
+    }
+
+    pub fn post_accounts_preview_order(
+        &self,
+        account_number: String,
+        body: model::OrderRequest,
+    ) -> Result<Request<'_, trader::PostAccountPreviewOrderRequest>, Error> {
+        self.request(self.inner.post_accounts_preview_order(account_number, body))
+    }
+
+    pub fn get_account_transactions(
+        &self,
+        account_number: String,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        types: TransactionType,
+    ) -> Result<Request<'_, trader::GetAccountTransactions>, Error> {
+        self.request(self.inner.get_account_transactions(
+            account_number,
+            start_date,
+            end_date,
+            types,
+        ))
+    }
+
+    /// See [`crate::api::Api::get_all_account_transactions`].
+    pub fn get_all_account_transactions(
+        &self,
+        account_number: String,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+        types: TransactionType,
+    ) -> Result<Vec<model::Transaction>, Error> {
+        self.rt.block_on(self.inner.get_all_account_transactions(
+            account_number,
+            start_date,
+            end_date,
+            types,
+        ))
+    }
+
+    pub fn get_account_transaction(
+        &self,
+        account_number: String,
+        transaction_id: i64,
+    ) -> Result<Request<'_, trader::GetAccountTransaction>, Error> {
+        self.request(
+            self.inner
+                .get_account_transaction(account_number, transaction_id),
+        )
+    }
+
+    pub fn get_user_preference(
+        &self,
+    ) -> Result<Request<'_, trader::GetUserPreferenceRequest>, Error> {
+        self.request(self.inner.get_user_preference())
+    }
+
+    /// See [`crate::api::Api::streamer_info`].
+    pub fn streamer_info(
+        &self,
+    ) -> Result<crate::model::trader::user_preference::StreamerInfo, Error> {
+        self.rt.block_on(self.inner.streamer_info())
+    }
+}
+
+fn runtime() -> Result<Runtime, Error> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(std::convert::Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Dummy {
+        val: i64,
+    }
+
+    impl Dummy {
+        fn val(&mut self, val: i64) -> &mut Self {
+            self.val = val;
+            self
+        }
+    }
+
+    impl AsyncRequest for Dummy {
+        type Output = i64;
+
+        async fn send(self) -> Result<Self::Output, Error> {
+            tokio::task::yield_now().await;
+            Ok(self.val)
+        }
+    }
+
+    #[test]
+    fn test_request_send() {
+        let rt = runtime().unwrap();
+        let mut req = Request {
+            req: Dummy { val: 0 },
+            rt: &rt,
+        };
+        // setters through `DerefMut`
+        req.val(10);
+        assert_eq!(req.val, 10);
+
+        assert_eq!(req.send().unwrap(), 10);
+    }
+}
@@ -6,16 +6,216 @@ pub enum Error {
     Stdio(#[from] std::io::Error),
     #[error("Token error: {0}")]
     Token(String),
-    #[error("Reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    #[error("Symbol error: {0}")]
+    Symbol(String),
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+    #[error("Transport error: {0}")]
+    Reqwest(reqwest::Error),
+    /// The request exceeded the timeout of the client, see [`crate::api::client_builder`].
+    #[error("Timeout: {0}")]
+    Timeout(reqwest::Error),
     #[error("OrderRequestBuild error: {0}")]
     OrderRequestBuild(crate::model::trader::order_request::OrderRequestBuilderError),
-    #[error("QuoteError: {0:?}")]
+    #[error("Quote error: {0}")]
     Quote(crate::model::QuoteError),
-    #[error("ErrorResponse: {0:?}")]
-    Response(crate::model::ErrorResponse),
-    #[error("ServiceError: {0:?}")]
-    Service(crate::model::ServiceError),
+    /// A documented error of the market data API from `url`
+    #[error("Market Data error: {status} from {url}: {error}")]
+    Response {
+        /// The requested URL
+        url: String,
+        status: reqwest::StatusCode,
+        error: crate::model::ErrorResponse,
+    },
+    /// A documented error of the trader API from `url`
+    #[error("Trader error: {status} from {url}: {error}")]
+    Service {
+        /// The requested URL
+        url: String,
+        status: reqwest::StatusCode,
+        error: crate::model::ServiceError,
+    },
+    /// `429 Too Many Requests` from `url`
+    #[error("Rate limited by {url}{}", retry_after.map_or_else(String::new, |x| format!(", retry after {x:?}")))]
+    RateLimited {
+        url: String,
+        retry_after: Option<std::time::Duration>,
+    },
+    /// `401 Unauthorized`
+    ///
+    /// Requests made by [`crate::Api`] are sent once more after [`crate::token::Tokener::refresh_access_token`],
+    /// so this is returned when the refreshed token is rejected as well, or with `reauth_required` when the refresh fails,
+    /// e.g. when the refresh token was revoked, then [`crate::token::Tokener::redo_authorization`] is needed.
+    #[error("Unauthorized{}", if *reauth_required { ", authorization required" } else { "" })]
+    Unauthorized { reauth_required: bool },
+    #[error("API error: {0}")]
+    Api(ApiError),
+    #[error("Order not cancelable: {0}")]
+    NotCancelable(crate::model::ServiceError),
+    /// The order reached a terminal status other than `FILLED`, e.g. `REJECTED` or `CANCELED`, see [`crate::Api::wait_for_fill`].
+    #[error("Order {} not filled: {:?}", .0.order_id, .0.status)]
+    OrderNotFilled(Box<crate::model::Order>),
+    /// The order was still open when the wait ran out, see [`crate::Api::wait_for_fill`].
+    #[error("Order {} still {:?} after waiting", .0.order_id, .0.status)]
+    OrderWaitTimeout(Box<crate::model::Order>),
+    #[error("Account error: {0}")]
+    Account(String),
+    #[error("Location error: {0}")]
+    Location(String),
     #[error("Json error: {0}")]
     Json(#[from] serde_json::Error),
+    /// The response body of `endpoint` doesn't match the model, e.g. when Schwab's schema drifts.
+    #[error("Deserialize error from {endpoint}: {source}")]
+    Deserialize {
+        /// The requested URL
+        endpoint: String,
+        source: serde_json::Error,
+        /// The raw response body
+        body: String,
+    },
+    #[error("WebSocket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("Streamer error: {0}")]
+    Streamer(String),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        if value.is_timeout() {
+            Self::Timeout(value)
+        } else {
+            Self::Reqwest(value)
+        }
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(value: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(value))
+    }
+}
+
+/// A non-successful HTTP response whose body doesn't match Schwab's documented error models.
+#[derive(Debug)]
+pub struct ApiError {
+    /// The requested URL
+    pub url: String,
+    pub status: reqwest::StatusCode,
+    /// The raw response body
+    pub body: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} from {}", self.status, self.url)?;
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the `Retry-After` header, which is either a number of seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.to_utc() - chrono::Utc::now())
+        .to_std()
+        .ok()
+        .or(Some(std::time::Duration::ZERO))
+}
+
+/// Deserialize the response body, keeping the body in [`Error::Deserialize`] if it doesn't match `T`.
+pub(crate) async fn deserialize<T: serde::de::DeserializeOwned>(
+    rsp: reqwest::Response,
+) -> Result<T, Error> {
+    let endpoint = rsp.url().to_string();
+    let body = rsp.text().await?;
+    serde_json::from_str(&body).map_err(|source| Error::Deserialize {
+        endpoint,
+        source,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("90"));
+        assert_eq!(
+            retry_after(&headers),
+            Some(std::time::Duration::from_secs(90))
+        );
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(std::time::Duration::ZERO));
+
+        let date = (chrono::Utc::now() + chrono::TimeDelta::minutes(10)).to_rfc2822();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let duration = retry_after(&headers).unwrap();
+        assert!((590..=600).contains(&duration.as_secs()));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let error = Error::Api(ApiError {
+            url: "https://api.schwabapi.com/marketdata/v1/markets".to_string(),
+            status: reqwest::StatusCode::BAD_GATEWAY,
+            body: "Bad Gateway".to_string(),
+        });
+        assert_eq!(
+            error.to_string(),
+            "API error: 502 Bad Gateway from https://api.schwabapi.com/marketdata/v1/markets: Bad Gateway"
+        );
+
+        let error = Error::RateLimited {
+            url: "https://api.schwabapi.com/trader/v1/accounts".to_string(),
+            retry_after: Some(std::time::Duration::from_secs(3)),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Rate limited by https://api.schwabapi.com/trader/v1/accounts, retry after 3s"
+        );
+
+        let error = Error::Service {
+            url: "https://api.schwabapi.com/trader/v1/orders/123".to_string(),
+            status: reqwest::StatusCode::NOT_FOUND,
+            error: crate::model::ServiceError {
+                message: Some("order not found".to_string()),
+                errors: Some(vec![crate::model::trader::service_error::ErrorDetail {
+                    id: "1".to_string(),
+                    status: 404,
+                    title: "Not Found".to_string(),
+                    detail: "order 123 not found".to_string(),
+                }]),
+            },
+        };
+        assert_eq!(
+            error.to_string(),
+            "Trader error: 404 Not Found from https://api.schwabapi.com/trader/v1/orders/123: order not found; 404 Not Found: order 123 not found"
+        );
+    }
 }
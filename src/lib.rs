@@ -13,7 +13,6 @@
 //! ## Example
 //! ```no_run
 //! use std::path::PathBuf;
-//! use reqwest::Client;
 //!
 //! use schwab_api::api;
 //! use schwab_api::token::TokenChecker;
@@ -29,7 +28,7 @@
 //!         .join("Schwab-rust.json");
 //!     let certs_dir = PathBuf::from("your_certs_dir");
 //!
-//!     let client = Client::new();
+//!     let client = api::client_builder().build().unwrap();
 //!     let token_checker = TokenChecker::new(path, key, secret, callback_url, certs_dir, client.clone())
 //!         .await
 //!         .unwrap();
@@ -42,6 +41,23 @@
 //! }
 //! ```
 //!
+//! ## Authorization
+//! [`token::TokenChecker::builder`] is the usual entry point: it loads the token from its path,
+//! refreshes the access token if it expired, or runs the browser flow if there is no usable refresh token,
+//! and saves the result. [`token::TokenChecker::new`] and [`token::TokenChecker::new_with_auth_manually`]
+//! do the same with positional arguments.
+//!
+//! A request rejected with `401 Unauthorized` is sent once more after a silent refresh of the access token,
+//! by the async and the blocking API alike.
+//!
+//! ## Features
+//! * `rustls-tls` (default): use rustls for the HTTP client and the local HTTPS redirect server.
+//! * `native-tls`: use the platform's native TLS (OpenSSL on Linux) instead, with `default-features = false`.
+//! * `decimal`: use `rust_decimal::Decimal` instead of `f64` for price fields, see [`model::Price`].
+//! * `blocking`: a blocking API for code without an async runtime, see `blocking::Api`.
+//! * `tracing`: emit [`tracing`](https://docs.rs/tracing) events and spans for the auth flow, requests, retries and streamer reconnects.
+//! * `strict-schema`: reject unknown fields in the option chain and market hours models, to catch schema drift in tests.
+//!
 //! **Disclaimer:** *This is an unofficial API wrapper for Schwab. It is not endorsed by or affiliated with Schwab or any associated organization. Before using this package, make sure to read and understand the terms of service of the underlying API. The authors of this package accept no responsibility for any damage that might stem from its use. Refer to the LICENSE file for more details.*
 
 #![forbid(unsafe_code)]
@@ -61,10 +77,20 @@ unused_allocation
     clippy::module_name_repetitions
 )]
 
+#[cfg(not(any(feature = "rustls-tls", feature = "native-tls")))]
+compile_error!("either the `rustls-tls` or the `native-tls` feature must be enabled");
+
+#[macro_use]
+mod log;
+
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod error;
 pub mod model;
+pub mod streamer;
 pub mod token;
 
 pub use api::Api;
 pub use error::Error;
+pub use model::QuoteError;
@@ -0,0 +1,30 @@
+//! Logging through `tracing` when the `tracing` feature is enabled, otherwise nothing is logged.
+
+macro_rules! log_event {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        log_event!(debug, $($arg)+)
+    };
+}
+
+macro_rules! info {
+    ($($arg:tt)+) => {
+        log_event!(info, $($arg)+)
+    };
+}
+
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        log_event!(warn, $($arg)+)
+    };
+}
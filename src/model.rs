@@ -1,8 +1,61 @@
 //! Structs and utilities for handling API response data.
 
 pub mod market_data;
+pub mod streamer;
 pub mod trader;
 
+/// Type of price fields, `rust_decimal::Decimal` with the `decimal` feature, `f64` otherwise.
+///
+/// With `decimal`, prices and balances are parsed through their shortest decimal representation,
+/// so `187.42` becomes exactly `187.42` rather than the nearest binary float. Prices sent as JSON
+/// strings are parsed exactly, and prices are serialized as JSON numbers.
+#[cfg(feature = "decimal")]
+pub type Price = rust_decimal::Decimal;
+/// Type of price fields, `rust_decimal::Decimal` with the `decimal` feature, `f64` otherwise.
+#[cfg(not(feature = "decimal"))]
+pub type Price = f64;
+
+/// Epoch milliseconds as either a JSON number or a string, serialized as a number.
+pub(crate) type EpochMillis = serde_with::PickFirst<(
+    serde_with::TimestampMilliSeconds<i64>,
+    serde_with::TimestampMilliSeconds<String>,
+)>;
+
+/// `x` as a [`Price`], e.g. to multiply a price by a quantity.
+///
+/// With the `decimal` feature, NaN or a value out of the range of `Decimal` is zero.
+pub(crate) fn to_price(x: f64) -> Price {
+    #[cfg(feature = "decimal")]
+    return Price::try_from(x).unwrap_or_default();
+    #[cfg(not(feature = "decimal"))]
+    return x;
+}
+
+#[cfg(test)]
+pub(crate) fn price(x: f64) -> Price {
+    #[cfg(feature = "decimal")]
+    return Price::try_from(x).expect("valid price");
+    #[cfg(not(feature = "decimal"))]
+    return x;
+}
+
+/// JSON snapshots of the response models, e.g. to persist them to disk for backtesting
+pub trait JsonExt: serde::Serialize + serde::de::DeserializeOwned {
+    /// Parse a snapshot written by [`Self::to_json`], or a raw response body.
+    fn from_json(s: &str) -> Result<Self, crate::Error> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Serialize into a JSON snapshot.
+    fn to_json(&self) -> Result<String, crate::Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl JsonExt for OptionChain {}
+impl JsonExt for Markets {}
+impl JsonExt for UserPreferences {}
+
 pub use market_data::candle_list::CandleList;
 pub use market_data::error_response::ErrorResponse;
 pub use market_data::expiration_chain::ExpirationChain;
@@ -16,6 +69,7 @@ pub use market_data::quote_response::QuoteResponse;
 pub(crate) use market_data::quote_response::QuoteResponseMap;
 
 pub use trader::account_number::AccountNumbers;
+pub use trader::account_number::AccountNumbersExt;
 pub use trader::accounts::Account;
 pub use trader::accounts::Accounts;
 pub use trader::order::Order;
@@ -25,4 +79,52 @@ pub use trader::preview_order::Instruction;
 pub use trader::preview_order::PreviewOrder;
 pub use trader::service_error::ServiceError;
 pub use trader::transactions::Transaction;
+pub use trader::user_preference::StreamerInfo;
 pub use trader::user_preference::UserPreferences;
+
+pub use streamer::level_one_equity::LevelOneEquity;
+pub use streamer::level_one_equity::LevelOneEquityField;
+pub use streamer::StreamerMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_ext() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = OptionChain::from_json(json).unwrap();
+        assert_eq!(
+            OptionChain::from_json(&chain.to_json().unwrap()).unwrap(),
+            chain
+        );
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/Markets.json"
+        ));
+        let markets = Markets::from_json(json).unwrap();
+        assert_eq!(
+            Markets::from_json(&markets.to_json().unwrap()).unwrap(),
+            markets
+        );
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/UserPreferences_real.json"
+        ));
+        let preferences = UserPreferences::from_json(json).unwrap();
+        assert_eq!(
+            UserPreferences::from_json(&preferences.to_json().unwrap()).unwrap(),
+            preferences
+        );
+
+        assert!(matches!(
+            OptionChain::from_json("{}"),
+            Err(crate::Error::Json(_))
+        ));
+    }
+}
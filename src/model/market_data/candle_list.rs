@@ -19,6 +19,56 @@ pub struct CandleList {
     pub symbol: String,
 }
 
+impl CandleList {
+    /// Candles with `datetime` from `start` inclusive to `end` exclusive.
+    pub fn candles_between(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> impl Iterator<Item = &Candle> {
+        self.candles
+            .iter()
+            .filter(move |x| start <= x.datetime && x.datetime < end)
+    }
+
+    /// Aggregate the candles into bars of `interval`, e.g. 1-minute candles into 5-minute bars.
+    ///
+    /// Buckets are aligned to the Unix epoch and dated by their start, buckets without candles are skipped.
+    /// `interval` is truncated to whole milliseconds like `datetime`, so nothing is returned if it is shorter than that.
+    #[must_use]
+    pub fn resample(&self, interval: std::time::Duration) -> Vec<Candle> {
+        let interval = i64::try_from(interval.as_millis()).unwrap_or(i64::MAX);
+        if interval == 0 {
+            return Vec::new();
+        }
+
+        let mut candles: Vec<_> = self.candles.iter().collect();
+        candles.sort_by_key(|x| x.datetime);
+
+        let mut bars: Vec<Candle> = Vec::new();
+        for candle in candles {
+            let bucket = candle.datetime.timestamp_millis().div_euclid(interval) * interval;
+            // out of range only for an interval of millennia, starting before the earliest date
+            let datetime = chrono::DateTime::from_timestamp_millis(bucket)
+                .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+            match bars.last_mut() {
+                Some(bar) if bar.datetime == datetime => {
+                    bar.high = bar.high.max(candle.high);
+                    bar.low = bar.low.min(candle.low);
+                    bar.close = candle.close;
+                    bar.volume += candle.volume;
+                }
+                _ => bars.push(Candle {
+                    datetime,
+                    datetime_iso8601: None,
+                    ..*candle
+                }),
+            }
+        }
+        bars
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,6 +89,7 @@ mod tests {
     use super::*;
 
     use assert_json_diff::{assert_json_matches, CompareMode, Config, NumericMode};
+    use std::time::Duration;
 
     #[test]
     fn test_de() {
@@ -52,6 +103,70 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    fn candle_list(candles: &[(i64, f64, f64, f64, f64, u64)]) -> CandleList {
+        CandleList {
+            candles: candles
+                .iter()
+                .map(|&(minute, open, high, low, close, volume)| Candle {
+                    close,
+                    datetime: chrono::DateTime::from_timestamp(minute * 60, 0).unwrap(),
+                    datetime_iso8601: None,
+                    high,
+                    low,
+                    open,
+                    volume,
+                })
+                .collect(),
+            empty: Some(false),
+            previous_close: None,
+            previous_close_date: None,
+            previous_close_date_iso8601: None,
+            symbol: "XYZ".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_candles_between() {
+        let list = candle_list(&[
+            (0, 1.0, 1.0, 1.0, 1.0, 1),
+            (1, 1.0, 1.0, 1.0, 1.0, 1),
+            (2, 1.0, 1.0, 1.0, 1.0, 1),
+        ]);
+        let minute = |x: i64| chrono::DateTime::from_timestamp(x * 60, 0).unwrap();
+
+        let candles: Vec<_> = list.candles_between(minute(1), minute(2)).collect();
+        assert_eq!(candles, vec![&list.candles[1]]);
+        assert_eq!(list.candles_between(minute(0), minute(3)).count(), 3);
+        assert_eq!(list.candles_between(minute(3), minute(4)).count(), 0);
+    }
+
+    #[test]
+    #[allow(clippy::duration_suboptimal_units)] // `Duration::from_mins` needs Rust 1.91
+    fn test_resample() {
+        let list = candle_list(&[
+            (0, 10.0, 12.0, 9.0, 11.0, 100),
+            (1, 11.0, 15.0, 10.0, 14.0, 200),
+            (4, 14.0, 14.5, 8.0, 9.0, 300),
+            // nothing from minute 5 to 9
+            (10, 9.0, 9.5, 8.5, 9.2, 50),
+        ]);
+
+        let bars = list.resample(Duration::from_secs(5 * 60));
+        assert_eq!(
+            bars,
+            candle_list(&[(0, 10.0, 15.0, 8.0, 9.0, 600), (10, 9.0, 9.5, 8.5, 9.2, 50),]).candles
+        );
+
+        assert_eq!(list.resample(Duration::from_secs(60)), list.candles);
+        assert!(candle_list(&[])
+            .resample(Duration::from_secs(60 * 60))
+            .is_empty());
+
+        // shorter than the millisecond resolution of `datetime`
+        assert!(list.resample(Duration::ZERO).is_empty());
+        assert!(list.resample(Duration::from_micros(999)).is_empty());
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -41,6 +41,28 @@ pub struct Error {
     pub source: Option<ErrorSource>,
 }
 
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status as i32, self.title)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {detail}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Who is responsible for triggering these errors.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -68,7 +90,7 @@ pub struct ErrorSource {
 }
 
 /// The HTTP status code .
-#[derive(Debug, Clone, Copy, PartialEq, Serialize_repr, Deserialize_repr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
 #[repr(i32)]
 pub enum StatusCode {
     /// 400 Bad Request
@@ -103,4 +125,30 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    fn test_display() {
+        let val = ErrorResponse {
+            errors: vec![
+                Error {
+                    id: "1".to_string(),
+                    status: StatusCode::BadRequest,
+                    title: "Bad Request".to_string(),
+                    detail: Some("Search combination should not exceed 500.".to_string()),
+                    source: None,
+                },
+                Error {
+                    id: "2".to_string(),
+                    status: StatusCode::Unauthorized,
+                    title: "Unauthorized".to_string(),
+                    detail: None,
+                    source: None,
+                },
+            ],
+        };
+        assert_eq!(
+            val.to_string(),
+            "400 Bad Request: Search combination should not exceed 500.; 401 Unauthorized"
+        );
+    }
 }
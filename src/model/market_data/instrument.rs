@@ -144,7 +144,7 @@ pub struct Bond {
     pub type_filed: Option<InstrumentAssetType>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum InstrumentAssetType {
     Bond,
@@ -168,6 +168,7 @@ mod custom_date_format {
 
     const FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
 
+    #[allow(clippy::ref_option)]
     pub fn serialize<S>(date: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -10,6 +10,7 @@ pub type Markets = HashMap<String, HashMap<String, Hours>>;
 )]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Hours {
     pub date: chrono::NaiveDate,
     pub market_type: MarketType,
@@ -28,7 +29,7 @@ pub struct Interval {
     pub end: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MarketType {
     Bond,
@@ -64,6 +65,31 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_serde() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/Markets.json"
+        ));
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let val = serde_json::from_value::<Markets>(json.clone()).unwrap();
+
+        let message = assert_json_matches_no_panic(
+            &val,
+            &json,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat),
+        )
+        .unwrap_err();
+
+        // same instants, formatted with another offset
+        let re = regex::Regex::new(r"(?:json atoms at path.*start.*are not equal.*\n.*\n.*\n.*\n.*)|(?:json atoms at path.*end.*are not equal.*\n.*\n.*\n.*\n.*)").unwrap();
+        let message = re.replace_all(&message, "");
+        let message = message.trim();
+        println!("{message}");
+        assert_eq!(message, "");
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -8,7 +8,6 @@ pub struct Mover {
 }
 
 /// Security info of most moved with in an index
-/// #[serde_with::apply(
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -35,7 +34,7 @@ pub struct Screener {
     pub net_percent_change: Option<f64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     Up,
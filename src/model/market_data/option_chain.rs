@@ -1,14 +1,16 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
-use std::collections::HashMap;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use std::collections::{BTreeMap, HashMap};
 
 use super::quote_response::option::ExerciseType;
 use super::quote_response::option::ExpirationType;
 use super::quote_response::option::SettlementType;
+use crate::model::{EpochMillis, Price};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct OptionChain {
     pub symbol: String,
     pub status: String,
@@ -20,46 +22,184 @@ pub struct OptionChain {
     pub is_index: bool,
     pub days_to_expiration: f64,
     pub interest_rate: f64,
-    pub underlying_price: f64,
+    pub underlying_price: Price,
     pub volatility: f64,
     pub call_exp_date_map: HashMap<String, HashMap<String, Vec<OptionContract>>>,
     pub put_exp_date_map: HashMap<String, HashMap<String, Vec<OptionContract>>>,
 
     // not in schema
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub number_of_contracts: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_main_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_sub_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub is_chain_truncated: Option<bool>,
 }
 
+/// Quote of the underlying, only populated with `includeUnderlyingQuote=true`
+///
+/// Every field is optional, since Schwab may return an empty or partial object otherwise.
 #[serde_as]
+#[serde_with::apply(
+    Option => #[serde(skip_serializing_if = "Option::is_none")],
+)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Underlying {
-    pub ask: i64,
-    pub ask_size: i64,
-    pub bid: i64,
-    pub bid_size: i64,
-    pub change: i64,
-    pub close: i64,
-    pub delayed: bool,
-    pub description: String,
-    pub exchange_name: ExchangeName,
-    pub fifty_two_week_high: i64,
-    pub fifty_two_week_low: i64,
-    pub high_price: f64,
-    pub last: i64,
-    pub low_price: f64,
-    pub mark: i64,
-    pub mark_change: f64,
-    pub mark_percent_change: f64,
-    pub open_price: f64,
-    pub percent_change: f64,
-    pub quote_time: i64,
-    pub symbol: String,
-    pub total_volume: u64,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
-    pub trade_time: chrono::DateTime<chrono::Utc>,
+    pub ask: Option<f64>,
+    pub ask_size: Option<i64>,
+    pub bid: Option<f64>,
+    pub bid_size: Option<i64>,
+    pub change: Option<f64>,
+    pub close: Option<f64>,
+    pub delayed: Option<bool>,
+    pub description: Option<String>,
+    pub exchange_name: Option<ExchangeName>,
+    pub fifty_two_week_high: Option<f64>,
+    pub fifty_two_week_low: Option<f64>,
+    pub high_price: Option<f64>,
+    pub last: Option<f64>,
+    pub low_price: Option<f64>,
+    pub mark: Option<f64>,
+    pub mark_change: Option<f64>,
+    pub mark_percent_change: Option<f64>,
+    pub open_price: Option<f64>,
+    pub percent_change: Option<f64>,
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub quote_time: Option<i64>,
+    pub symbol: Option<String>,
+    pub total_volume: Option<u64>,
+    #[serde_as(as = "Option<EpochMillis>")]
+    pub trade_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Decode a key of [`OptionChain::call_exp_date_map`] or [`OptionChain::put_exp_date_map`] into the expiration date and days to expiration
+///
+/// e.g. `"2024-06-21:7"`. Without the `:days` suffix, days are counted from today in UTC, see [`parse_exp_key_from`].
+#[must_use]
+pub fn parse_exp_key(key: &str) -> Option<(chrono::NaiveDate, i64)> {
+    parse_exp_key_from(key, chrono::Utc::now().date_naive())
+}
+
+/// Same as [`parse_exp_key`], counting days from `today` without the `:days` suffix.
+#[must_use]
+pub fn parse_exp_key_from(key: &str, today: chrono::NaiveDate) -> Option<(chrono::NaiveDate, i64)> {
+    let (date, days) = match key.split_once(':') {
+        Some((date, days)) => (date, Some(days)),
+        None => (key, None),
+    };
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let days = match days {
+        Some(days) => days.parse().ok()?,
+        None => (date - today).num_days(),
+    };
+    Some((date, days))
+}
+
+impl OptionChain {
+    /// All call contracts, across expirations and strikes
+    pub fn calls(&self) -> impl Iterator<Item = &OptionContract> {
+        Self::contracts(&self.call_exp_date_map)
+    }
+
+    /// All put contracts, across expirations and strikes
+    pub fn puts(&self) -> impl Iterator<Item = &OptionContract> {
+        Self::contracts(&self.put_exp_date_map)
+    }
+
+    fn contracts(
+        map: &HashMap<String, HashMap<String, Vec<OptionContract>>>,
+    ) -> impl Iterator<Item = &OptionContract> {
+        map.values().flat_map(HashMap::values).flatten()
+    }
+
+    fn exp_date_map(
+        &self,
+        put_call: PutCall,
+    ) -> &HashMap<String, HashMap<String, Vec<OptionContract>>> {
+        match put_call {
+            PutCall::Call => &self.call_exp_date_map,
+            PutCall::Put => &self.put_exp_date_map,
+        }
+    }
+
+    /// Contracts of `put_call` by expiration date, each sorted by strike
+    ///
+    /// Expirations whose key [`parse_exp_key`] can't decode are skipped.
+    #[must_use]
+    pub fn by_expiration(
+        &self,
+        put_call: PutCall,
+    ) -> BTreeMap<chrono::NaiveDate, Vec<&OptionContract>> {
+        let mut expirations = BTreeMap::new();
+        for (key, strikes) in self.exp_date_map(put_call) {
+            let Some((date, _)) = parse_exp_key(key) else {
+                continue;
+            };
+            let contracts: &mut Vec<_> = expirations.entry(date).or_default();
+            contracts.extend(strikes.values().flatten());
+            contracts.sort_by(|a, b| {
+                a.strike_price
+                    .partial_cmp(&b.strike_price)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        expirations
+    }
+
+    /// Contracts of `put_call` with `min <= delta <= max`, sorted by expiration then strike
+    ///
+    /// Put deltas are negative, e.g. `by_delta(PutCall::Put, -0.30, -0.20)`.
+    #[must_use]
+    pub fn by_delta(&self, put_call: PutCall, min: f64, max: f64) -> Vec<&OptionContract> {
+        let mut contracts: Vec<_> = Self::contracts(self.exp_date_map(put_call))
+            .filter(|x| (min..=max).contains(&x.delta))
+            .collect();
+        contracts.sort_by(|a, b| {
+            a.expiration_date.cmp(&b.expiration_date).then_with(|| {
+                a.strike_price
+                    .partial_cmp(&b.strike_price)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+        contracts
+    }
+
+    /// The contract with the delta closest to `target`, calls and puts alike since put deltas are negative
+    #[must_use]
+    pub fn nearest_delta(&self, target: f64) -> Option<&OptionContract> {
+        self.calls()
+            .chain(self.puts())
+            .filter(|x| x.delta.is_finite())
+            .min_by(|a, b| {
+                (a.delta - target)
+                    .abs()
+                    .total_cmp(&(b.delta - target).abs())
+            })
+    }
+
+    /// Whether both the chain and the underlying quote are realtime, rather than 15-minute-delayed
+    ///
+    /// [`Self::is_delayed`] takes precedence, then [`Underlying::delayed`] has to be `false`.
+    /// Without the underlying quote (see `includeUnderlyingQuote`) or its flag, the data isn't known to be realtime, so `false`.
+    #[must_use]
+    pub fn is_realtime(&self) -> bool {
+        !self.is_delayed && self.underlying.as_ref().and_then(|x| x.delayed) == Some(false)
+    }
+}
+
+impl Underlying {
+    /// Returns the quote time in Utc format, `trade_time` is already in Utc format
+    ///
+    /// `None` if `quote_time` is missing or out of the range of [`chrono::DateTime`]
+    #[must_use]
+    pub fn quote_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.quote_time
+            .and_then(chrono::DateTime::from_timestamp_millis)
+    }
 }
 
 #[serde_as]
@@ -69,28 +209,29 @@ pub struct Underlying {
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct OptionContract {
     pub put_call: PutCall,
     pub symbol: String,
     pub description: String,
     pub exchange_name: String,
-    pub bid_price: Option<f64>,
-    pub ask_price: Option<f64>,
-    pub last_price: Option<f64>,
-    pub mark_price: Option<f64>,
+    pub bid_price: Option<Price>,
+    pub ask_price: Option<Price>,
+    pub last_price: Option<Price>,
+    pub mark_price: Option<Price>,
     pub bid_size: i64,
     pub ask_size: i64,
     pub last_size: i64,
-    pub high_price: f64,
-    pub low_price: f64,
-    pub open_price: f64,
-    pub close_price: f64,
+    pub high_price: Price,
+    pub low_price: Price,
+    pub open_price: Price,
+    pub close_price: Price,
     pub total_volume: u64,
-    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    #[serde_as(as = "Option<EpochMillis>")]
     pub trade_date: Option<chrono::DateTime<chrono::Utc>>,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde_as(as = "EpochMillis")]
     pub quote_time_in_long: chrono::DateTime<chrono::Utc>,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde_as(as = "EpochMillis")]
     pub trade_time_in_long: chrono::DateTime<chrono::Utc>,
     pub net_change: f64,
     pub volatility: f64,
@@ -107,11 +248,11 @@ pub struct OptionContract {
     pub is_mini: Option<bool>,
     pub is_non_standard: Option<bool>,
     pub option_deliverables_list: Vec<OptionDeliverable>,
-    pub strike_price: f64,
+    pub strike_price: Price,
     pub expiration_date: chrono::DateTime<chrono::Utc>,
     pub days_to_expiration: i64,
     pub expiration_type: ExpirationType,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde_as(as = "EpochMillis")]
     pub last_trading_day: chrono::DateTime<chrono::Utc>,
     pub multiplier: f64,
     pub settlement_type: SettlementType,
@@ -140,6 +281,37 @@ pub struct OptionContract {
     pub penny_pilot: Option<bool>,
 }
 
+impl OptionContract {
+    /// `bid_price` and `ask_price`, `None` if either side is missing or not positive.
+    fn quoted_sides(&self) -> Option<(Price, Price)> {
+        let positive = |x: Option<Price>| x.filter(|x| *x > Price::default());
+        Some((positive(self.bid_price)?, positive(self.ask_price)?))
+    }
+
+    /// Mid price between `bid_price` and `ask_price`, `None` if either side is missing or not positive.
+    #[must_use]
+    pub fn mid(&self) -> Option<Price> {
+        let (bid, ask) = self.quoted_sides()?;
+        Some((bid + ask) / Price::from(2))
+    }
+
+    /// `ask_price` - `bid_price`, `None` if either side is missing or not positive.
+    #[must_use]
+    pub fn spread(&self) -> Option<Price> {
+        let (bid, ask) = self.quoted_sides()?;
+        Some(ask - bid)
+    }
+
+    /// Days from `reference` to the UTC date of `expiration_date`, unlike the `days_to_expiration` snapshot.
+    ///
+    /// `None` if the contract already expired before `reference`.
+    #[must_use]
+    pub fn days_to_expiration_from(&self, reference: chrono::NaiveDate) -> Option<i64> {
+        let days = (self.expiration_date.date_naive() - reference).num_days();
+        (days >= 0).then_some(days)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OptionDeliverable {
@@ -151,7 +323,7 @@ pub struct OptionDeliverable {
 }
 
 /// Available values : `SINGLE`, `ANALYTICAL`, `COVERED`, `VERTICAL`, `CALENDAR`, `STRANGLE`, `STRADDLE`, `BUTTERFLY`, `CONDOR`, `DIAGONAL`, `COLLAR`, `ROLL`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Strategy {
     Single,
@@ -169,7 +341,7 @@ pub enum Strategy {
 }
 
 /// Available values : IND, ASE, NYS, NAS, NAP, PAC, OPR, BATS
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum ExchangeName {
     Ind,
@@ -183,7 +355,7 @@ pub enum ExchangeName {
 }
 
 /// Available values : `PUT`, `CALL`
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PutCall {
     Put,
@@ -194,6 +366,8 @@ pub enum PutCall {
 mod tests {
     use super::*;
 
+    use crate::model::price;
+
     use assert_json_diff::{assert_json_matches_no_panic, CompareMode, Config, NumericMode};
 
     #[test]
@@ -208,6 +382,384 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[cfg(feature = "strict-schema")]
+    #[test]
+    fn test_de_strict_schema() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(serde_json::from_value::<OptionChain>(json.clone()).is_ok());
+
+        json["underlying"]["newField"] = serde_json::Value::Bool(true);
+        let err = serde_json::from_value::<OptionChain>(json).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `newField`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_de_include_underlying_quote() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_underlying.json"
+        ));
+        let underlying = serde_json::from_str::<OptionChain>(json)
+            .unwrap()
+            .underlying
+            .unwrap();
+        assert_eq!(underlying.symbol.as_deref(), Some("AAPL"));
+        assert_eq!(underlying.bid, Some(189.9));
+        assert_eq!(underlying.ask, Some(189.92));
+        assert_eq!(underlying.last, Some(189.91));
+        assert_eq!(underlying.exchange_name, Some(ExchangeName::Nas));
+        assert_eq!(
+            underlying.quote_time(),
+            chrono::DateTime::from_timestamp_millis(1_715_976_000_123)
+        );
+
+        let out_of_range = Underlying {
+            quote_time: Some(i64::MAX),
+            ..underlying
+        };
+        assert_eq!(out_of_range.quote_time(), None);
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_underlying_nulls.json"
+        ));
+        let underlying = serde_json::from_str::<OptionChain>(json)
+            .unwrap()
+            .underlying
+            .unwrap();
+        assert_eq!(underlying.symbol.as_deref(), Some("AAPL"));
+        assert_eq!(underlying.bid, None);
+        assert_eq!(underlying.total_volume, None);
+        assert_eq!(underlying.quote_time(), None);
+        assert_eq!(underlying.trade_time, None);
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_no_underlying.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+        assert_eq!(chain.underlying, None);
+        assert!(!chain.is_realtime());
+        assert!(!chain.call_exp_date_map.is_empty());
+    }
+
+    #[test]
+    fn test_by_delta() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+
+        let puts = chain.by_delta(PutCall::Put, -0.30, -0.20);
+        assert!(!puts.is_empty());
+        assert!(puts
+            .iter()
+            .all(|x| x.put_call == PutCall::Put && (-0.30..=-0.20).contains(&x.delta)));
+        assert_eq!(
+            puts.len(),
+            chain
+                .puts()
+                .filter(|x| (-0.30..=-0.20).contains(&x.delta))
+                .count()
+        );
+        assert!(puts
+            .windows(2)
+            .all(|x| x[0].expiration_date <= x[1].expiration_date));
+
+        let calls = chain.by_delta(PutCall::Call, 0.45, 0.55);
+        assert!(calls.iter().all(|x| x.put_call == PutCall::Call));
+        assert!(chain.by_delta(PutCall::Call, 2.0, 3.0).is_empty());
+
+        let nearest = chain.nearest_delta(-0.25).unwrap();
+        assert_eq!(nearest.put_call, PutCall::Put);
+        assert!(chain
+            .calls()
+            .chain(chain.puts())
+            .all(|x| (x.delta + 0.25).abs() >= (nearest.delta + 0.25).abs()));
+    }
+
+    #[test]
+    fn test_parse_exp_key() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert_eq!(parse_exp_key("2024-06-21:7"), Some((date, 7)));
+
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 6, 18).unwrap();
+        assert_eq!(parse_exp_key_from("2024-06-21", today), Some((date, 3)));
+        // the suffix wins over `today`
+        assert_eq!(parse_exp_key_from("2024-06-21:7", today), Some((date, 7)));
+
+        assert_eq!(parse_exp_key("2024-06-21:seven"), None);
+        assert_eq!(parse_exp_key("06/21/2024:7"), None);
+        assert_eq!(parse_exp_key(""), None);
+    }
+
+    #[test]
+    fn test_by_expiration() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+
+        let calls = chain.by_expiration(PutCall::Call);
+        assert_eq!(calls.len(), chain.call_exp_date_map.len());
+        assert_eq!(
+            calls.keys().next(),
+            chrono::NaiveDate::from_ymd_opt(2024, 5, 17).as_ref()
+        );
+        assert_eq!(
+            calls.values().map(Vec::len).sum::<usize>(),
+            chain.calls().count()
+        );
+        for (date, contracts) in &calls {
+            assert!(contracts
+                .iter()
+                .all(|x| x.put_call == PutCall::Call && x.expiration_date.date_naive() == *date));
+            assert!(contracts
+                .windows(2)
+                .all(|x| x[0].strike_price <= x[1].strike_price));
+        }
+    }
+
+    #[test]
+    fn test_is_realtime() {
+        let de = |json: &str| serde_json::from_str::<OptionChain>(json).unwrap();
+
+        // delayed chain and underlying
+        let chain = de(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain.json"
+        )));
+        assert!(chain.is_delayed);
+        assert!(!chain.is_realtime());
+
+        let mut chain = de(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_underlying.json"
+        )));
+        assert!(chain.is_realtime());
+
+        // the chain flag takes precedence
+        chain.is_delayed = true;
+        assert!(!chain.is_realtime());
+
+        // unknown delay of the underlying
+        chain.is_delayed = false;
+        chain.underlying.as_mut().unwrap().delayed = None;
+        assert!(!chain.is_realtime());
+        chain.underlying = None;
+        assert!(!chain.is_realtime());
+    }
+
+    #[test]
+    fn test_days_to_expiration_from() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+        let contracts = chain.by_expiration(PutCall::Call);
+        let (expiration, contracts) = contracts
+            .iter()
+            .find(|(_, x)| x[0].days_to_expiration == 14)
+            .unwrap();
+        let contract = contracts[0];
+
+        // the snapshot was taken on 2024-05-17
+        let snapshot = chrono::NaiveDate::from_ymd_opt(2024, 5, 17).unwrap();
+        assert_eq!(
+            *expiration,
+            chrono::NaiveDate::from_ymd_opt(2024, 5, 31).unwrap()
+        );
+        assert_eq!(contract.days_to_expiration_from(snapshot), Some(14));
+        assert_eq!(
+            contract.days_to_expiration_from(chrono::NaiveDate::from_ymd_opt(2024, 5, 30).unwrap()),
+            Some(1)
+        );
+        assert_eq!(contract.days_to_expiration_from(*expiration), Some(0));
+        assert_eq!(
+            contract.days_to_expiration_from(chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_group_by_put_call() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+        let len = |map: &HashMap<String, HashMap<String, Vec<OptionContract>>>| {
+            map.values()
+                .flat_map(HashMap::values)
+                .map(Vec::len)
+                .sum::<usize>()
+        };
+
+        let mut counts = HashMap::<PutCall, usize>::new();
+        for contract in chain
+            .call_exp_date_map
+            .values()
+            .chain(chain.put_exp_date_map.values())
+            .flat_map(HashMap::values)
+            .flatten()
+        {
+            *counts.entry(contract.put_call).or_default() += 1;
+        }
+        assert_eq!(counts[&PutCall::Call], len(&chain.call_exp_date_map));
+        assert_eq!(counts[&PutCall::Put], len(&chain.put_exp_date_map));
+    }
+
+    #[test]
+    fn test_underlying_quote_time() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain.json"
+        ));
+
+        let mut underlying = serde_json::from_str::<OptionChain>(json)
+            .unwrap()
+            .underlying
+            .unwrap();
+        assert_eq!(underlying.quote_time(), Some(chrono::DateTime::UNIX_EPOCH));
+
+        underlying.quote_time = Some(1_715_976_000_123);
+        assert_eq!(
+            underlying.quote_time(),
+            Some(
+                "2024-05-17T20:00:00.123Z"
+                    .parse::<chrono::DateTime<chrono::Utc>>()
+                    .unwrap()
+            )
+        );
+
+        underlying.quote_time = None;
+        assert_eq!(underlying.quote_time(), None);
+    }
+
+    #[test]
+    fn test_de_epoch_as_string() {
+        fn stringify(val: &mut serde_json::Value, fields: &[&str]) {
+            for field in fields {
+                if let Some(x) = val.get_mut(*field) {
+                    *x = serde_json::Value::String(x.to_string());
+                }
+            }
+        }
+
+        for json in [
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/OptionChain.json"
+            )),
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/OptionChain_real.json"
+            )),
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/OptionChain_underlying.json"
+            )),
+        ] {
+            let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+            let expected = serde_json::from_value::<OptionChain>(json.clone()).unwrap();
+
+            if let Some(underlying) = json.get_mut("underlying") {
+                stringify(underlying, &["quoteTime", "tradeTime"]);
+            }
+            for map in ["callExpDateMap", "putExpDateMap"] {
+                for contracts in json[map]
+                    .as_object_mut()
+                    .unwrap()
+                    .values_mut()
+                    .flat_map(|x| x.as_object_mut().unwrap().values_mut())
+                {
+                    for contract in contracts.as_array_mut().unwrap() {
+                        stringify(
+                            contract,
+                            &[
+                                "tradeDate",
+                                "quoteTimeInLong",
+                                "tradeTimeInLong",
+                                "lastTradingDay",
+                            ],
+                        );
+                    }
+                }
+            }
+
+            let val = serde_json::from_value::<OptionChain>(json).unwrap();
+            assert_eq!(val, expected);
+        }
+    }
+
+    #[test]
+    fn test_option_contract_mid_spread() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let chain = serde_json::from_str::<OptionChain>(json).unwrap();
+        let mut contract = chain
+            .call_exp_date_map
+            .values()
+            .flat_map(HashMap::values)
+            .flatten()
+            .next()
+            .unwrap()
+            .clone();
+
+        contract.bid_price = Some(price(1.2));
+        contract.ask_price = Some(price(1.5));
+        assert_eq!(contract.mid(), Some(price(1.35)));
+        assert_eq!(contract.spread(), Some(price(1.5) - price(1.2)));
+
+        contract.bid_price = Some(price(0.0));
+        assert_eq!(contract.mid(), None);
+        assert_eq!(contract.spread(), None);
+
+        contract.bid_price = Some(price(1.2));
+        contract.ask_price = None;
+        assert_eq!(contract.mid(), None);
+        assert_eq!(contract.spread(), None);
+    }
+
+    #[test]
+    fn test_serde() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain.json"
+        ));
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let val = serde_json::from_value::<OptionChain>(json.clone()).unwrap();
+
+        let message = assert_json_matches_no_panic(
+            &val,
+            &json,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat),
+        )
+        .unwrap_err();
+
+        // same instants, formatted with another offset
+        let re =
+            regex::Regex::new(r"(?:json atoms at path.*Date.*are not equal.*\n.*\n.*\n.*\n.*)")
+                .unwrap();
+        let message = re.replace_all(&message, "");
+        let message = message.trim();
+        println!("{message}");
+        assert_eq!(message, "");
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -7,7 +7,7 @@ pub mod mutual_fund;
 pub mod option;
 pub mod quote_error;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,7 +21,11 @@ pub(crate) struct QuoteResponseMap {
 
 /// a (symbol, `QuoteResponse`) map. `SCHWis` an example key
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "assetMainType", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(
+    remote = "Self",
+    tag = "assetMainType",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
 pub enum QuoteResponse {
     Bond(String),
     Equity(Box<equity::EquityResponse>),
@@ -31,14 +35,51 @@ pub enum QuoteResponse {
     Index(index::IndexResponse),
     MutualFund(mutual_fund::MutualFundResponse),
     Option(Box<option::OptionResponse>),
+    /// Error of a single symbol, an entry without `assetMainType`
+    #[serde(skip)]
+    Error(quote_error::QuoteError),
+}
+
+impl Serialize for QuoteResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            QuoteResponse::Error(x) => x.serialize(serializer),
+            _ => QuoteResponse::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for QuoteResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const ERROR_FIELDS: &[&str] =
+            &["message", "invalidCusips", "invalidSSIDs", "invalidSymbols"];
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value.get("assetMainType").is_some() {
+            QuoteResponse::deserialize(value).map_err(serde::de::Error::custom)
+        } else if ERROR_FIELDS.iter().any(|x| value.get(x).is_some()) {
+            quote_error::QuoteError::deserialize(value)
+                .map(QuoteResponse::Error)
+                .map_err(serde::de::Error::custom)
+        } else {
+            Err(serde::de::Error::custom(
+                "expected a quote with `assetMainType` or an error with `message` or `invalid*`",
+            ))
+        }
+    }
 }
 
 impl QuoteResponse {
-    /// Returns the symbol of the quote
+    /// Returns the symbol of the quote, or the first invalid symbol of [`QuoteResponse::Error`]
     #[must_use]
     pub fn symbol(&self) -> &str {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
+            QuoteResponse::Error(x) => x
+                .invalid_symbols
+                .as_ref()
+                .and_then(|x| x.first())
+                .map_or("", String::as_str),
             QuoteResponse::Equity(x) => &x.symbol,
             QuoteResponse::Forex(x) => &x.symbol,
             QuoteResponse::Future(x) => &x.symbol,
@@ -54,13 +95,14 @@ impl QuoteResponse {
     pub fn n52week_high(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.n52week_high),
-            QuoteResponse::Forex(x) => Some(x.quote.n52week_high),
-            QuoteResponse::Index(x) => Some(x.quote.n52week_high),
-            QuoteResponse::MutualFund(x) => Some(x.quote.n52week_high),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.n52week_high),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.n52week_high),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.n52week_high),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().map(|q| q.n52week_high),
             QuoteResponse::Future(_)
             | QuoteResponse::FutureOption(_)
-            | QuoteResponse::Option(_) => None,
+            | QuoteResponse::Option(_)
+            | QuoteResponse::Error(_) => None,
         }
     }
 
@@ -69,13 +111,14 @@ impl QuoteResponse {
     pub fn n52week_low(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.n52week_low),
-            QuoteResponse::Forex(x) => Some(x.quote.n52week_low),
-            QuoteResponse::Index(x) => Some(x.quote.n52week_low),
-            QuoteResponse::MutualFund(x) => Some(x.quote.n52week_low),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.n52week_low),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.n52week_low),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.n52week_low),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().map(|q| q.n52week_low),
             QuoteResponse::Future(_)
             | QuoteResponse::FutureOption(_)
-            | QuoteResponse::Option(_) => None,
+            | QuoteResponse::Option(_)
+            | QuoteResponse::Error(_) => None,
         }
     }
 
@@ -84,12 +127,14 @@ impl QuoteResponse {
     pub fn ask_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.ask_price),
-            QuoteResponse::Forex(x) => Some(x.quote.ask_price),
-            QuoteResponse::Future(x) => Some(x.quote.ask_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.ask_price),
-            QuoteResponse::Option(x) => Some(x.quote.ask_price),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.ask_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.ask_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.ask_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.ask_price),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.ask_price),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
         }
     }
 
@@ -98,12 +143,14 @@ impl QuoteResponse {
     pub fn ask_size(&self) -> Option<i64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.ask_size),
-            QuoteResponse::Forex(x) => Some(x.quote.ask_size),
-            QuoteResponse::Future(x) => Some(x.quote.ask_size),
-            QuoteResponse::FutureOption(x) => Some(x.quote.ask_size),
-            QuoteResponse::Option(x) => Some(x.quote.ask_size),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.ask_size),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.ask_size),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.ask_size),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.ask_size),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.ask_size),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
         }
     }
 
@@ -112,13 +159,14 @@ impl QuoteResponse {
     pub fn ask_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.ask_time),
-            QuoteResponse::Future(x) => Some(x.quote.ask_time),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.ask_time),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.ask_time),
+            QuoteResponse::Option(x) => x.quote.as_ref().and_then(|q| q.ask_time),
             QuoteResponse::Forex(_)
             | QuoteResponse::FutureOption(_)
             | QuoteResponse::Index(_)
             | QuoteResponse::MutualFund(_)
-            | QuoteResponse::Option(_) => None,
+            | QuoteResponse::Error(_) => None,
         }
     }
 
@@ -127,12 +175,14 @@ impl QuoteResponse {
     pub fn bid_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.bid_price),
-            QuoteResponse::Forex(x) => Some(x.quote.bid_price),
-            QuoteResponse::Future(x) => Some(x.quote.bid_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.bid_price),
-            QuoteResponse::Option(x) => Some(x.quote.bid_price),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.bid_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.bid_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.bid_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.bid_price),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.bid_price),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
         }
     }
 
@@ -141,12 +191,14 @@ impl QuoteResponse {
     pub fn bid_size(&self) -> Option<i64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.bid_size),
-            QuoteResponse::Forex(x) => Some(x.quote.bid_size),
-            QuoteResponse::Future(x) => Some(x.quote.bid_size),
-            QuoteResponse::FutureOption(x) => Some(x.quote.bid_size),
-            QuoteResponse::Option(x) => Some(x.quote.bid_size),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.bid_size),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.bid_size),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.bid_size),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.bid_size),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.bid_size),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
         }
     }
 
@@ -155,28 +207,30 @@ impl QuoteResponse {
     pub fn bid_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.bid_time),
-            QuoteResponse::Future(x) => Some(x.quote.bid_time),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.bid_time),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.bid_time),
+            QuoteResponse::Option(x) => x.quote.as_ref().and_then(|q| q.bid_time),
             QuoteResponse::Forex(_)
             | QuoteResponse::FutureOption(_)
             | QuoteResponse::Index(_)
             | QuoteResponse::MutualFund(_)
-            | QuoteResponse::Option(_) => None,
+            | QuoteResponse::Error(_) => None,
         }
     }
 
     /// Returns the previous day's closing price
     #[must_use]
-    pub fn close_price(&self) -> f64 {
+    pub fn close_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => x.quote.close_price,
-            QuoteResponse::Forex(x) => x.quote.close_price,
-            QuoteResponse::Future(x) => x.quote.close_price,
-            QuoteResponse::FutureOption(x) => x.quote.close_price,
-            QuoteResponse::Index(x) => x.quote.close_price,
-            QuoteResponse::MutualFund(x) => x.quote.close_price,
-            QuoteResponse::Option(x) => x.quote.close_price,
+            QuoteResponse::Error(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().map(|q| q.close_price),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.close_price),
         }
     }
 
@@ -185,13 +239,13 @@ impl QuoteResponse {
     pub fn high_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.high_price),
-            QuoteResponse::Forex(x) => Some(x.quote.high_price),
-            QuoteResponse::Future(x) => Some(x.quote.high_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.high_price),
-            QuoteResponse::Index(x) => Some(x.quote.high_price),
-            QuoteResponse::MutualFund(_) => None,
-            QuoteResponse::Option(x) => Some(x.quote.high_price),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.high_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.high_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.high_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.high_price),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.high_price),
+            QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => None,
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.high_price),
         }
     }
 
@@ -200,13 +254,13 @@ impl QuoteResponse {
     pub fn last_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.last_price),
-            QuoteResponse::Forex(x) => Some(x.quote.last_price),
-            QuoteResponse::Future(x) => Some(x.quote.last_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.last_price),
-            QuoteResponse::Index(x) => Some(x.quote.last_price),
-            QuoteResponse::MutualFund(_) => None,
-            QuoteResponse::Option(x) => Some(x.quote.last_price),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.last_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.last_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.last_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.last_price),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.last_price),
+            QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => None,
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.last_price),
         }
     }
 
@@ -215,12 +269,14 @@ impl QuoteResponse {
     pub fn last_size(&self) -> Option<i64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.last_size),
-            QuoteResponse::Forex(x) => Some(x.quote.last_size),
-            QuoteResponse::Future(x) => Some(x.quote.last_size),
-            QuoteResponse::FutureOption(x) => Some(x.quote.last_size),
-            QuoteResponse::Option(x) => Some(x.quote.last_size),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.last_size),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.last_size),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.last_size),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.last_size),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.last_size),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
         }
     }
 
@@ -229,28 +285,29 @@ impl QuoteResponse {
     pub fn low_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.low_price),
-            QuoteResponse::Forex(x) => Some(x.quote.low_price),
-            QuoteResponse::Future(x) => Some(x.quote.low_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.low_price),
-            QuoteResponse::Index(x) => Some(x.quote.low_price),
-            QuoteResponse::MutualFund(_) => None,
-            QuoteResponse::Option(x) => Some(x.quote.low_price),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.low_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.low_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.low_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.low_price),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.low_price),
+            QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => None,
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.low_price),
         }
     }
 
     /// Returns the current last-prev close price difference
     #[must_use]
-    pub fn net_change(&self) -> f64 {
+    pub fn net_change(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => x.quote.net_change,
-            QuoteResponse::Forex(x) => x.quote.net_change,
-            QuoteResponse::Future(x) => x.quote.net_change,
-            QuoteResponse::FutureOption(x) => x.quote.net_change,
-            QuoteResponse::Index(x) => x.quote.net_change,
-            QuoteResponse::MutualFund(x) => x.quote.net_change,
-            QuoteResponse::Option(x) => x.quote.net_change,
+            QuoteResponse::Error(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().map(|q| q.net_change),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.net_change),
         }
     }
 
@@ -259,13 +316,13 @@ impl QuoteResponse {
     pub fn open_price(&self) -> Option<f64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.open_price),
-            QuoteResponse::Forex(x) => Some(x.quote.open_price),
-            QuoteResponse::Future(x) => Some(x.quote.open_price),
-            QuoteResponse::FutureOption(x) => Some(x.quote.open_price),
-            QuoteResponse::Index(x) => Some(x.quote.open_price),
-            QuoteResponse::MutualFund(_) => None,
-            QuoteResponse::Option(x) => Some(x.quote.open_price),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.open_price),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.open_price),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.open_price),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.open_price),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.open_price),
+            QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => None,
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.open_price),
         }
     }
 
@@ -274,27 +331,30 @@ impl QuoteResponse {
     pub fn quote_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.quote_time),
-            QuoteResponse::Forex(x) => Some(x.quote.quote_time),
-            QuoteResponse::Future(x) => Some(x.quote.quote_time),
-            QuoteResponse::FutureOption(x) => Some(x.quote.quote_time),
-            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) => None,
-            QuoteResponse::Option(x) => Some(x.quote.quote_time),
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.quote_time),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.quote_time),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.quote_time),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.quote_time),
+            QuoteResponse::Index(_) | QuoteResponse::MutualFund(_) | QuoteResponse::Error(_) => {
+                None
+            }
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.quote_time),
         }
     }
 
     /// Returns the time of the last trade in Utc format
     #[must_use]
-    pub fn trade_time(&self) -> chrono::DateTime<chrono::Utc> {
+    pub fn trade_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => x.quote.trade_time,
-            QuoteResponse::Forex(x) => x.quote.trade_time,
-            QuoteResponse::Future(x) => x.quote.trade_time,
-            QuoteResponse::FutureOption(x) => x.quote.trade_time,
-            QuoteResponse::Index(x) => x.quote.trade_time,
-            QuoteResponse::MutualFund(x) => x.quote.trade_time,
-            QuoteResponse::Option(x) => x.quote.trade_time,
+            QuoteResponse::Error(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().map(|q| q.trade_time),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.trade_time),
         }
     }
 
@@ -303,13 +363,14 @@ impl QuoteResponse {
     pub fn total_volume(&self) -> Option<u64> {
         match self {
             QuoteResponse::Bond(x) => unimplemented!("{x}"),
-            QuoteResponse::Equity(x) => Some(x.quote.total_volume),
-            QuoteResponse::Forex(x) => Some(x.quote.total_volume),
-            QuoteResponse::Future(x) => Some(x.quote.total_volume),
-            QuoteResponse::FutureOption(x) => Some(x.quote.total_volume),
-            QuoteResponse::Index(x) => Some(x.quote.total_volume),
-            QuoteResponse::MutualFund(x) => x.quote.total_volume,
-            QuoteResponse::Option(x) => Some(x.quote.total_volume),
+            QuoteResponse::Error(_) => None,
+            QuoteResponse::Equity(x) => x.quote.as_ref().map(|q| q.total_volume),
+            QuoteResponse::Forex(x) => x.quote.as_ref().map(|q| q.total_volume),
+            QuoteResponse::Future(x) => x.quote.as_ref().map(|q| q.total_volume),
+            QuoteResponse::FutureOption(x) => x.quote.as_ref().map(|q| q.total_volume),
+            QuoteResponse::Index(x) => x.quote.as_ref().map(|q| q.total_volume),
+            QuoteResponse::MutualFund(x) => x.quote.as_ref().and_then(|q| q.total_volume),
+            QuoteResponse::Option(x) => x.quote.as_ref().map(|q| q.total_volume),
         }
     }
 }
@@ -333,6 +394,23 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_serde() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse.json"
+        ));
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let val = serde_json::from_value::<QuoteResponseMap>(json.clone()).unwrap();
+
+        assert_json_matches!(
+            val,
+            json,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -351,6 +429,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_de_subset() {
+        // `fields=quote` omits `reference` and the other root nodes
+        let json = serde_json::json!({
+            "AAPL": {
+                "assetMainType": "EQUITY",
+                "symbol": "AAPL",
+                "quoteType": "NBBO",
+                "realtime": true,
+                "ssid": 1_973_757_747,
+                "quote": {
+                    "52WeekHigh": 199.62,
+                    "52WeekLow": 164.075,
+                    "askMICId": "ARCX",
+                    "askPrice": 189.92,
+                    "askSize": 1,
+                    "askTime": 1_715_990_363_904_i64,
+                    "bidMICId": "ARCX",
+                    "bidPrice": 189.9,
+                    "bidSize": 6,
+                    "bidTime": 1_715_990_363_904_i64,
+                    "closePrice": 189.84,
+                    "highPrice": 190.81,
+                    "lastMICId": "XADF",
+                    "lastPrice": 189.9,
+                    "lastSize": 2,
+                    "lowPrice": 189.18,
+                    "mark": 189.87,
+                    "markChange": 0.03,
+                    "markPercentChange": 0.0158,
+                    "netChange": 0.06,
+                    "netPercentChange": 0.0316,
+                    "openPrice": 189.51,
+                    "postMarketChange": 0.03,
+                    "postMarketPercentChange": 0.0158,
+                    "quoteTime": 1_715_990_363_904_i64,
+                    "securityStatus": "Normal",
+                    "totalVolume": 41_282_925,
+                    "tradeTime": 1_715_990_395_834_i64
+                }
+            },
+            "MSFT": {
+                "assetMainType": "EQUITY",
+                "symbol": "MSFT",
+                "quoteType": "NBBO",
+                "realtime": true,
+                "ssid": 1_283_633_000
+            }
+        });
+
+        let mut val = serde_json::from_value::<QuoteResponseMap>(json.clone()).unwrap();
+        let aapl = val.responses.remove("AAPL").unwrap();
+        assert_approx_eq!(f64, 189.92, aapl.ask_price().unwrap());
+        let QuoteResponse::Equity(x) = &aapl else {
+            panic!("not an equity")
+        };
+        assert!(x.reference.is_none());
+
+        let msft = val.responses.remove("MSFT").unwrap();
+        assert_eq!("MSFT", msft.symbol());
+        assert_eq!(None, msft.ask_price());
+        assert_eq!(None, msft.close_price());
+    }
+
+    #[test]
+    fn test_serde_error() {
+        let json = serde_json::json!({
+            "FOO": {
+                "message": "Symbol not found",
+                "invalidSymbols": ["FOO"]
+            }
+        });
+        let val = serde_json::from_value::<HashMap<String, QuoteResponse>>(json.clone()).unwrap();
+        let QuoteResponse::Error(e) = &val["FOO"] else {
+            panic!("should be QuoteResponse::Error: {val:?}");
+        };
+        assert_eq!(e.message.as_deref(), Some("Symbol not found"));
+        assert_eq!(val["FOO"].symbol(), "FOO");
+        assert_eq!(val["FOO"].last_price(), None);
+        assert_eq!(serde_json::to_value(&val).unwrap(), json);
+
+        // a malformed quote is still an error, not a `QuoteResponse::Error`
+        let json = serde_json::json!({ "assetMainType": "EQUITY", "symbol": 1 });
+        assert!(serde_json::from_value::<QuoteResponse>(json).is_err());
+
+        // neither a quote nor an error
+        let json = serde_json::json!({ "symbol": "FOO", "quote": {} });
+        assert!(serde_json::from_value::<QuoteResponse>(json).is_err());
+        assert!(serde_json::from_value::<QuoteResponse>(serde_json::json!({})).is_err());
+    }
+
     #[test]
     fn test_methods() {
         let json = include_str!(concat!(
@@ -377,12 +546,12 @@ mod tests {
             chrono::DateTime::from_timestamp_millis(1_715_990_363_904).unwrap(),
             result.bid_time().unwrap()
         );
-        assert_approx_eq!(f64, 189.84, result.close_price());
+        assert_approx_eq!(f64, 189.84, result.close_price().unwrap());
         assert_approx_eq!(f64, 190.81, result.high_price().unwrap());
         assert_approx_eq!(f64, 189.9, result.last_price().unwrap());
         assert_eq!(2, result.last_size().unwrap());
         assert_approx_eq!(f64, 189.18, result.low_price().unwrap());
-        assert_approx_eq!(f64, 0.06, result.net_change());
+        assert_approx_eq!(f64, 0.06, result.net_change().unwrap());
         assert_approx_eq!(f64, 189.51, result.open_price().unwrap());
         assert_eq!(
             chrono::DateTime::from_timestamp_millis(1_715_990_363_904).unwrap(),
@@ -390,8 +559,18 @@ mod tests {
         );
         assert_eq!(
             chrono::DateTime::from_timestamp_millis(1_715_990_395_834).unwrap(),
-            result.trade_time()
+            result.trade_time().unwrap()
         );
         assert_eq!(41_282_925, result.total_volume().unwrap());
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse.json"
+        ));
+        let mut val = serde_json::from_str::<QuoteResponseMap>(json).unwrap();
+        let result = val.responses.remove("AMZN  220617C03170000").unwrap();
+        assert!(matches!(result, QuoteResponse::Option(_)));
+        assert_eq!(chrono::DateTime::UNIX_EPOCH, result.ask_time().unwrap());
+        assert_eq!(chrono::DateTime::UNIX_EPOCH, result.bid_time().unwrap());
     }
 }
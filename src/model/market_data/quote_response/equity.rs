@@ -34,11 +34,81 @@ pub struct EquityResponse {
     pub quote_type: QuoteType,
     pub extended: Option<ExtendedMarket>,
     pub fundamental: Option<Fundamental>,
-    pub quote: QuoteEquity,
-    pub reference: ReferenceEquity,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    pub quote: Option<QuoteEquity>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    pub reference: Option<ReferenceEquity>,
+
     pub regular: Option<RegularMarket>,
 }
 
+impl EquityResponse {
+    /// Current best bid price
+    #[must_use]
+    pub fn bid(&self) -> Option<f64> {
+        self.quote.as_ref().map(|x| x.bid_price)
+    }
+
+    /// Current best ask price
+    #[must_use]
+    pub fn ask(&self) -> Option<f64> {
+        self.quote.as_ref().map(|x| x.ask_price)
+    }
+
+    /// Midpoint of [`Self::bid`] and [`Self::ask`], `None` if either side is not positive
+    #[must_use]
+    pub fn mid(&self) -> Option<f64> {
+        self.quote
+            .as_ref()
+            .filter(|x| x.bid_price > 0.0 && x.ask_price > 0.0)
+            .map(|x| f64::midpoint(x.bid_price, x.ask_price))
+    }
+
+    /// Last traded price
+    #[must_use]
+    pub fn last(&self) -> Option<f64> {
+        self.quote.as_ref().map(|x| x.last_price)
+    }
+
+    /// Current Last-Prev Close
+    #[must_use]
+    pub fn net_change(&self) -> Option<f64> {
+        self.quote.as_ref().map(|x| x.net_change)
+    }
+
+    /// Aggregated shares traded throughout the day, including pre/post market hours.
+    #[must_use]
+    pub fn total_volume(&self) -> Option<u64> {
+        self.quote.as_ref().map(|x| x.total_volume)
+    }
+
+    /// P/E Ratio, `None` if `fundamental` was not requested
+    #[must_use]
+    pub fn pe_ratio(&self) -> Option<f64> {
+        self.fundamental.as_ref().map(|x| x.pe_ratio)
+    }
+
+    /// Earnings per Share, `None` if `fundamental` was not requested
+    #[must_use]
+    pub fn eps(&self) -> Option<f64> {
+        self.fundamental.as_ref().map(|x| x.eps)
+    }
+
+    /// Dividend yield in percent, `None` if `fundamental` was not requested
+    #[must_use]
+    pub fn dividend_yield(&self) -> Option<f64> {
+        self.fundamental.as_ref().map(|x| x.div_yield)
+    }
+
+    /// Annual dividend amount, `None` if `fundamental` was not requested
+    #[must_use]
+    pub fn dividend_amount(&self) -> Option<f64> {
+        self.fundamental.as_ref().map(|x| x.div_amount)
+    }
+}
+
 /// Quote data for extended hours
 #[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -420,7 +490,10 @@ pub struct RegularMarket {
     /// example: -1.1246
     ///
     /// Regular market percent change
-    #[serde(rename = "regularMarketPercentChange")]
+    #[serde(
+        rename = "regularMarketPercentChange",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub percent_change: Option<f64>,
 
     /// example: 1621368000400
@@ -432,7 +505,7 @@ pub struct RegularMarket {
 }
 
 /// Asset Sub Type (only there if applicable)
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum EquityAssetSubType {
     Coe,
@@ -447,7 +520,7 @@ pub enum EquityAssetSubType {
     Rgt,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QuoteType {
     /// NBBO - realtime
@@ -457,7 +530,7 @@ pub enum QuoteType {
 }
 
 /// Dividend frequency
-#[derive(Debug, Clone, Copy, PartialEq, Serialize_repr, Deserialize_repr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum DivFrequency {
     /// null
@@ -486,7 +559,7 @@ pub enum DivFrequency {
 }
 
 /// Fund Strategy
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FundStrategy {
     ///  "A" - Active
@@ -515,6 +588,7 @@ pub enum FundStrategy {
 mod tests {
     use super::*;
 
+    use float_cmp::assert_approx_eq;
     use std::collections::HashMap;
 
     #[test]
@@ -528,4 +602,52 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    fn test_helpers() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse/EquityResponse.json"
+        ));
+
+        let mut val = serde_json::from_str::<HashMap<String, EquityResponse>>(json).unwrap();
+        let aapl = val.remove("AAPL").unwrap();
+        assert_approx_eq!(f64, aapl.bid().unwrap(), 168.4);
+        assert_approx_eq!(f64, aapl.ask().unwrap(), 168.41);
+        assert_approx_eq!(f64, aapl.mid().unwrap(), 168.405);
+        assert_approx_eq!(f64, aapl.last().unwrap(), 168.405);
+        assert_approx_eq!(f64, aapl.net_change().unwrap(), -9.165);
+        assert_eq!(aapl.total_volume(), Some(22_361_159));
+
+        // no bid, e.g. outside market hours
+        let mut aapl = aapl;
+        aapl.quote.as_mut().unwrap().bid_price = 0.0;
+        assert_approx_eq!(f64, aapl.bid().unwrap(), 0.0);
+        assert_eq!(aapl.mid(), None);
+
+        aapl.quote = None;
+        assert_eq!(aapl.bid(), None);
+        assert_eq!(aapl.mid(), None);
+    }
+
+    #[test]
+    fn test_fundamental() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        ));
+
+        let mut val = serde_json::from_str::<HashMap<String, serde_json::Value>>(json).unwrap();
+        let mut aapl =
+            serde_json::from_value::<EquityResponse>(val.remove("AAPL").unwrap()).unwrap();
+        assert_eq!(aapl.pe_ratio(), Some(29.571_75));
+        assert_eq!(aapl.eps(), Some(6.13));
+        assert_eq!(aapl.dividend_yield(), Some(0.527_09));
+        assert_eq!(aapl.dividend_amount(), Some(1.0));
+
+        // not requested by `fields`
+        aapl.fundamental = None;
+        assert_eq!(aapl.pe_ratio(), None);
+        assert_eq!(aapl.dividend_yield(), None);
+    }
 }
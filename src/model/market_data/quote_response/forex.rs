@@ -21,8 +21,14 @@ pub struct ForexResponse {
     ///
     /// is quote realtime
     pub realtime: bool,
-    pub quote: QuoteForex,
-    pub reference: ReferenceForex,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteForex>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceForex>,
 }
 
 /// Quote data of Forex security
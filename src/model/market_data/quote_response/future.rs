@@ -20,8 +20,14 @@ pub struct FutureResponse {
     ///
     /// is quote realtime
     pub realtime: bool,
-    pub quote: QuoteFuture,
-    pub reference: ReferenceFuture,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteFuture>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceFuture>,
 }
 
 /// Quote data of Future security
@@ -22,8 +22,14 @@ pub struct FutureOptionResponse {
     ///
     /// is quote realtime
     pub realtime: bool,
-    pub quote: QuoteFutureOption,
-    pub reference: ReferenceFutureOption,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteFutureOption>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceFutureOption>,
 }
 
 /// Quote data of Option security
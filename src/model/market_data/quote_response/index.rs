@@ -20,10 +20,17 @@ pub struct IndexResponse {
     ///
     /// is quote realtime
     pub realtime: bool,
-    pub quote: QuoteIndex,
-    pub reference: ReferenceIndex,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteIndex>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceIndex>,
 
     // not in schama
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fundamental: Option<super::equity::Fundamental>,
 }
 
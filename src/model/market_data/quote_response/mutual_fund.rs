@@ -10,6 +10,7 @@ use super::equity::Fundamental;
 pub struct MutualFundResponse {
     /// nullable: true
     /// Asset Sub Type (only there if applicable)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_sub_type: Option<MutualFundAssetSubType>,
 
     /// example: 1234567890
@@ -27,8 +28,14 @@ pub struct MutualFundResponse {
     /// is quote realtime
     pub realtime: bool,
     pub fundamental: Option<Fundamental>,
-    pub quote: QuoteMutualFund,
-    pub reference: ReferenceMutualFund,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteMutualFund>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceMutualFund>,
 }
 
 /// Quote data of Mutual Fund security
@@ -87,6 +94,7 @@ pub struct QuoteMutualFund {
     pub trade_time: chrono::DateTime<chrono::Utc>,
 
     // not in schema
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub last_price: Option<f64>,
 }
 
@@ -116,7 +124,7 @@ pub struct ReferenceMutualFund {
 }
 
 /// Asset Sub Type (only there if applicable)
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MutualFundAssetSubType {
     Oef,
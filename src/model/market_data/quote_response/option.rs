@@ -20,8 +20,14 @@ pub struct OptionResponse {
     ///
     /// is quote realtime
     pub realtime: bool,
-    pub quote: QuoteOption,
-    pub reference: ReferenceOption,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteOption>,
+
+    /// `None` if not requested by [`crate::api::market_data::GetQuotesRequest::fields`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceOption>,
 }
 
 /// Quote data of Option security
@@ -32,13 +38,13 @@ pub struct QuoteOption {
     /// example: 145.09
     ///
     /// Higest price traded in the past 12 months, or 52 weeks
-    #[serde(rename = "52WeekHigh")]
+    #[serde(rename = "52WeekHigh", skip_serializing_if = "Option::is_none")]
     pub n52week_high: Option<f64>,
 
     /// example: 77.581
     ///
     /// Lowest price traded in the past 12 months, or 52 weeks
-    #[serde(rename = "52WeekLow")]
+    #[serde(rename = "52WeekLow", skip_serializing_if = "Option::is_none")]
     pub n52week_low: Option<f64>,
 
     /// example: 124.63
@@ -51,6 +57,11 @@ pub struct QuoteOption {
     /// Number of shares for ask
     pub ask_size: i64,
 
+    /// Last ask time in milliseconds since Epoch
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ask_time: Option<chrono::DateTime<chrono::Utc>>,
+
     /// example: 124.6
     ///
     /// Current Best Bid Price
@@ -61,6 +72,11 @@ pub struct QuoteOption {
     /// Number of shares for bid
     pub bid_size: i64,
 
+    /// Last bid time in milliseconds since Epoch
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bid_time: Option<chrono::DateTime<chrono::Utc>>,
+
     /// example: 126.27
     ///
     /// Previous day's closing price
@@ -315,7 +331,7 @@ pub struct ReferenceOption {
 }
 
 /// Indicates call or put
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ContractType {
     #[serde(rename = "P")]
@@ -325,7 +341,7 @@ pub enum ContractType {
 }
 
 /// option contract exercise type America or European
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExerciseType {
     #[serde(rename = "A")]
@@ -335,7 +351,7 @@ pub enum ExerciseType {
 }
 
 /// M for End Of Month Expiration Calendar Cycle. (To match the last business day of the month), Q for Quarterly expirations (last business day of the quarter month MAR/JUN/SEP/DEC), W for Weekly expiration (also called Friday Short Term Expirations) and S for Expires 3rd Friday of the month (also known as regular options).
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExpirationType {
     /// M for End Of Month Expiration Calendar Cycle. (To match the last business day of the month)
@@ -357,7 +373,7 @@ pub enum ExpirationType {
 }
 
 /// option contract settlement type AM or PM
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SettlementType {
     #[serde(rename = "A")]
@@ -1,8 +1,11 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-/// Partial or Custom errors per request
+/// Partial or Custom errors per request, or the error of a single symbol as [`super::QuoteResponse::Error`]
 #[allow(clippy::struct_field_names)]
+#[serde_with::apply(
+    Option => #[serde(default, skip_serializing_if = "Option::is_none")],
+)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteError {
@@ -15,6 +18,35 @@ pub struct QuoteError {
 
     ///list of invalid symbols from request
     pub invalid_symbols: Option<Vec<String>>,
+
+    /// Error message, if any
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        if let Some(message) = &self.message {
+            write!(f, "{message}")?;
+            first = false;
+        }
+        let parts = [
+            ("invalid symbols", &self.invalid_symbols),
+            ("invalid cusips", &self.invalid_cusips),
+            ("invalid SSIDs", &self.invalid_ssids),
+        ];
+        for (name, values) in parts {
+            let Some(values) = values else {
+                continue;
+            };
+            if !first {
+                write!(f, "; ")?;
+            }
+            first = false;
+            write!(f, "{name}: {}", values.join(", "))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
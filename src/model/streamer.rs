@@ -0,0 +1,240 @@
+//! Structs and utilities for handling [`crate::streamer`] messages.
+
+pub mod level_one_equity;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_with::{serde_as, TimestampMilliSeconds};
+use std::collections::HashMap;
+
+/// Requests sent to the streamer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamerRequests {
+    pub requests: Vec<StreamerRequest>,
+}
+
+#[serde_with::apply(
+    Option => #[serde(skip_serializing_if = "Option::is_none")],
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamerRequest {
+    pub service: Service,
+    /// Unique identifier of the request, echoed in the response
+    #[serde(rename = "requestid")]
+    pub request_id: String,
+    pub command: Command,
+    #[serde(rename = "SchwabClientCustomerId")]
+    pub schwab_client_customer_id: String,
+    #[serde(rename = "SchwabClientCorrelId")]
+    pub schwab_client_correl_id: String,
+    pub parameters: Option<HashMap<String, String>>,
+}
+
+/// Messages received from the streamer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamerMessage {
+    /// Responses to requests
+    Response(Vec<StreamerResponse>),
+    /// Heartbeats and service notifications
+    Notify(Vec<Notify>),
+    /// Subscribed data
+    Data(Vec<StreamerData>),
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamerResponse {
+    pub service: Service,
+    pub command: Command,
+    #[serde(rename = "requestid")]
+    pub request_id: String,
+    #[serde(rename = "SchwabClientCorrelId")]
+    pub schwab_client_correl_id: String,
+    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub content: ResponseContent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseContent {
+    /// 0 for success
+    pub code: i64,
+    pub msg: String,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Notify {
+    Heartbeat {
+        #[serde_as(as = "TimestampMilliSeconds<String>")]
+        heartbeat: chrono::DateTime<chrono::Utc>,
+    },
+    Service {
+        service: Service,
+        #[serde_as(as = "TimestampMilliSeconds<i64>")]
+        timestamp: chrono::DateTime<chrono::Utc>,
+        content: ResponseContent,
+    },
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamerData {
+    pub service: Service,
+    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub command: Command,
+    pub content: Vec<serde_json::Value>,
+}
+
+/// Streamer services
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Service {
+    Admin,
+    LeveloneEquities,
+    LeveloneOptions,
+    LeveloneFutures,
+    LeveloneFuturesOptions,
+    LeveloneForex,
+    NyseBook,
+    NasdaqBook,
+    OptionsBook,
+    ChartEquity,
+    ChartFutures,
+    ScreenerEquity,
+    ScreenerOption,
+    AcctActivity,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Streamer commands
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Command {
+    Login,
+    Logout,
+    Subs,
+    Add,
+    Unsubs,
+    View,
+    #[serde(untagged)]
+    Other(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_ser_request() {
+        let request = StreamerRequests {
+            requests: vec![StreamerRequest {
+                service: Service::Admin,
+                request_id: "0".to_string(),
+                command: Command::Login,
+                schwab_client_customer_id: "customer".to_string(),
+                schwab_client_correl_id: "correl".to_string(),
+                parameters: Some(HashMap::from([
+                    ("Authorization".to_string(), "token".to_string()),
+                    ("SchwabClientChannel".to_string(), "N9".to_string()),
+                    ("SchwabClientFunctionId".to_string(), "APIAPP".to_string()),
+                ])),
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            json!({
+                "requests": [
+                    {
+                        "service": "ADMIN",
+                        "requestid": "0",
+                        "command": "LOGIN",
+                        "SchwabClientCustomerId": "customer",
+                        "SchwabClientCorrelId": "correl",
+                        "parameters": {
+                            "Authorization": "token",
+                            "SchwabClientChannel": "N9",
+                            "SchwabClientFunctionId": "APIAPP"
+                        }
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_de_message() {
+        let json = json!({
+            "response": [
+                {
+                    "service": "ADMIN",
+                    "command": "LOGIN",
+                    "requestid": "0",
+                    "SchwabClientCorrelId": "correl",
+                    "timestamp": 1_715_976_000_123_i64,
+                    "content": {
+                        "code": 0,
+                        "msg": "server=s0635dc6-1;status=NP"
+                    }
+                }
+            ]
+        });
+        let val = serde_json::from_value::<StreamerMessage>(json).unwrap();
+        let StreamerMessage::Response(responses) = val else {
+            panic!("should be response");
+        };
+        assert_eq!(responses[0].service, Service::Admin);
+        assert_eq!(responses[0].command, Command::Login);
+        assert_eq!(responses[0].content.code, 0);
+
+        let json = json!({
+            "notify": [
+                { "heartbeat": "1715976000123" },
+                {
+                    "service": "ADMIN",
+                    "timestamp": 1_715_976_000_123_i64,
+                    "content": {
+                        "code": 30,
+                        "msg": "Stop streaming due to empty subscription"
+                    }
+                }
+            ]
+        });
+        let val = serde_json::from_value::<StreamerMessage>(json).unwrap();
+        let StreamerMessage::Notify(notify) = val else {
+            panic!("should be notify");
+        };
+        assert!(matches!(notify[0], Notify::Heartbeat { .. }));
+        assert!(matches!(&notify[1], Notify::Service { content, .. } if content.code == 30));
+
+        let json = json!({
+            "data": [
+                {
+                    "service": "NEW_SERVICE",
+                    "timestamp": 1_715_976_000_123_i64,
+                    "command": "SUBS",
+                    "content": [{ "key": "AAPL" }]
+                }
+            ]
+        });
+        let val = serde_json::from_value::<StreamerMessage>(json).unwrap();
+        let StreamerMessage::Data(data) = val else {
+            panic!("should be data");
+        };
+        assert_eq!(data[0].service, Service::Other("NEW_SERVICE".to_string()));
+        assert_eq!(data[0].content.len(), 1);
+    }
+}
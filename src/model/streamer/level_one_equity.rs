@@ -0,0 +1,342 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::Price;
+
+/// Fields of the `LEVELONE_EQUITIES` service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LevelOneEquityField {
+    Symbol = 0,
+    BidPrice = 1,
+    AskPrice = 2,
+    LastPrice = 3,
+    BidSize = 4,
+    AskSize = 5,
+    AskId = 6,
+    BidId = 7,
+    TotalVolume = 8,
+    LastSize = 9,
+    HighPrice = 10,
+    LowPrice = 11,
+    ClosePrice = 12,
+    ExchangeId = 13,
+    Marginable = 14,
+    Description = 15,
+    LastId = 16,
+    OpenPrice = 17,
+    NetChange = 18,
+    HighPrice52Week = 19,
+    LowPrice52Week = 20,
+    PeRatio = 21,
+    AnnualDividendAmount = 22,
+    DividendYield = 23,
+    Nav = 24,
+    ExchangeName = 25,
+    DividendDate = 26,
+    IsRegularMarketQuote = 27,
+    IsRegularMarketTrade = 28,
+    RegularMarketLastPrice = 29,
+    RegularMarketLastSize = 30,
+    RegularMarketNetChange = 31,
+    SecurityStatus = 32,
+    MarkPrice = 33,
+    QuoteTime = 34,
+    TradeTime = 35,
+    RegularMarketTradeTime = 36,
+    BidTime = 37,
+    AskTime = 38,
+    AskMicId = 39,
+    BidMicId = 40,
+    LastMicId = 41,
+    NetPercentChange = 42,
+    RegularMarketPercentChange = 43,
+    MarkPriceNetChange = 44,
+    MarkPricePercentChange = 45,
+    HardToBorrowQuantity = 46,
+    HardToBorrowRate = 47,
+    HardToBorrow = 48,
+    Shortable = 49,
+    PostMarketNetChange = 50,
+    PostMarketPercentChange = 51,
+}
+
+impl LevelOneEquityField {
+    /// The numeric field ID used by the streamer
+    #[must_use]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+/// An update of the `LEVELONE_EQUITIES` service
+///
+/// The streamer only sends the fields that changed since the last update,
+/// use [`LevelOneEquity::update`] to merge an update into the latest state.
+#[serde_as]
+#[serde_with::apply(
+    Option => #[serde(skip_serializing_if = "Option::is_none")],
+)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelOneEquity {
+    /// Ticker symbol in upper case
+    pub key: String,
+    pub delayed: Option<bool>,
+    pub asset_main_type: Option<String>,
+    pub asset_sub_type: Option<String>,
+    pub cusip: Option<String>,
+    #[serde(rename = "1")]
+    pub bid_price: Option<Price>,
+    #[serde(rename = "2")]
+    pub ask_price: Option<Price>,
+    #[serde(rename = "3")]
+    pub last_price: Option<Price>,
+    #[serde(rename = "4")]
+    pub bid_size: Option<i64>,
+    #[serde(rename = "5")]
+    pub ask_size: Option<i64>,
+    #[serde(rename = "6")]
+    pub ask_id: Option<String>,
+    #[serde(rename = "7")]
+    pub bid_id: Option<String>,
+    #[serde(rename = "8")]
+    pub total_volume: Option<i64>,
+    #[serde(rename = "9")]
+    pub last_size: Option<i64>,
+    #[serde(rename = "10")]
+    pub high_price: Option<Price>,
+    #[serde(rename = "11")]
+    pub low_price: Option<Price>,
+    #[serde(rename = "12")]
+    pub close_price: Option<Price>,
+    #[serde(rename = "13")]
+    pub exchange_id: Option<String>,
+    #[serde(rename = "14")]
+    pub marginable: Option<bool>,
+    #[serde(rename = "15")]
+    pub description: Option<String>,
+    #[serde(rename = "16")]
+    pub last_id: Option<String>,
+    #[serde(rename = "17")]
+    pub open_price: Option<Price>,
+    #[serde(rename = "18")]
+    pub net_change: Option<f64>,
+    #[serde(rename = "19")]
+    pub high_price_52_week: Option<Price>,
+    #[serde(rename = "20")]
+    pub low_price_52_week: Option<Price>,
+    #[serde(rename = "21")]
+    pub pe_ratio: Option<f64>,
+    #[serde(rename = "22")]
+    pub annual_dividend_amount: Option<f64>,
+    #[serde(rename = "23")]
+    pub dividend_yield: Option<f64>,
+    #[serde(rename = "24")]
+    pub nav: Option<f64>,
+    #[serde(rename = "25")]
+    pub exchange_name: Option<String>,
+    /// format: yyyy-mm-dd
+    #[serde(rename = "26")]
+    pub dividend_date: Option<String>,
+    #[serde(rename = "27")]
+    pub is_regular_market_quote: Option<bool>,
+    #[serde(rename = "28")]
+    pub is_regular_market_trade: Option<bool>,
+    #[serde(rename = "29")]
+    pub regular_market_last_price: Option<Price>,
+    #[serde(rename = "30")]
+    pub regular_market_last_size: Option<i64>,
+    #[serde(rename = "31")]
+    pub regular_market_net_change: Option<f64>,
+    #[serde(rename = "32")]
+    pub security_status: Option<String>,
+    #[serde(rename = "33")]
+    pub mark_price: Option<Price>,
+    #[serde(rename = "34")]
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    pub quote_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "35")]
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    pub trade_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "36")]
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    pub regular_market_trade_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "37")]
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    pub bid_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "38")]
+    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    pub ask_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "39")]
+    pub ask_mic_id: Option<String>,
+    #[serde(rename = "40")]
+    pub bid_mic_id: Option<String>,
+    #[serde(rename = "41")]
+    pub last_mic_id: Option<String>,
+    #[serde(rename = "42")]
+    pub net_percent_change: Option<f64>,
+    #[serde(rename = "43")]
+    pub regular_market_percent_change: Option<f64>,
+    #[serde(rename = "44")]
+    pub mark_price_net_change: Option<f64>,
+    #[serde(rename = "45")]
+    pub mark_price_percent_change: Option<f64>,
+    #[serde(rename = "46")]
+    pub hard_to_borrow_quantity: Option<i64>,
+    #[serde(rename = "47")]
+    pub hard_to_borrow_rate: Option<f64>,
+    /// 1 = true, 0 = false, -1 = unknown
+    #[serde(rename = "48")]
+    pub hard_to_borrow: Option<i64>,
+    /// 1 = true, 0 = false, -1 = unknown
+    #[serde(rename = "49")]
+    pub shortable: Option<i64>,
+    #[serde(rename = "50")]
+    pub post_market_net_change: Option<f64>,
+    #[serde(rename = "51")]
+    pub post_market_percent_change: Option<f64>,
+}
+
+impl LevelOneEquity {
+    /// Merge a partial update, overwriting the fields present in it.
+    pub fn update(&mut self, other: Self) {
+        macro_rules! merge {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if other.$field.is_some() {
+                        self.$field = other.$field;
+                    }
+                )*
+            };
+        }
+
+        self.key = other.key;
+        merge!(
+            delayed,
+            asset_main_type,
+            asset_sub_type,
+            cusip,
+            bid_price,
+            ask_price,
+            last_price,
+            bid_size,
+            ask_size,
+            ask_id,
+            bid_id,
+            total_volume,
+            last_size,
+            high_price,
+            low_price,
+            close_price,
+            exchange_id,
+            marginable,
+            description,
+            last_id,
+            open_price,
+            net_change,
+            high_price_52_week,
+            low_price_52_week,
+            pe_ratio,
+            annual_dividend_amount,
+            dividend_yield,
+            nav,
+            exchange_name,
+            dividend_date,
+            is_regular_market_quote,
+            is_regular_market_trade,
+            regular_market_last_price,
+            regular_market_last_size,
+            regular_market_net_change,
+            security_status,
+            mark_price,
+            quote_time,
+            trade_time,
+            regular_market_trade_time,
+            bid_time,
+            ask_time,
+            ask_mic_id,
+            bid_mic_id,
+            last_mic_id,
+            net_percent_change,
+            regular_market_percent_change,
+            mark_price_net_change,
+            mark_price_percent_change,
+            hard_to_borrow_quantity,
+            hard_to_borrow_rate,
+            hard_to_borrow,
+            shortable,
+            post_market_net_change,
+            post_market_percent_change,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::price;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_de() {
+        let json = json!({
+            "key": "AAPL",
+            "delayed": false,
+            "assetMainType": "EQUITY",
+            "assetSubType": "COE",
+            "cusip": "037833100",
+            "1": 189.5,
+            "2": 189.52,
+            "3": 189.51,
+            "4": 2,
+            "5": 4,
+            "8": 30_225_610,
+            "34": 1_715_976_000_123_i64,
+            "48": 0
+        });
+        let val = serde_json::from_value::<LevelOneEquity>(json).unwrap();
+        assert_eq!(val.key, "AAPL");
+        assert_eq!(val.bid_price, Some(price(189.5)));
+        assert_eq!(val.ask_price, Some(price(189.52)));
+        assert_eq!(val.total_volume, Some(30_225_610));
+        assert_eq!(
+            val.quote_time.map(|x| x.timestamp_millis()),
+            Some(1_715_976_000_123)
+        );
+        assert_eq!(val.hard_to_borrow, Some(0));
+        assert_eq!(val.mark_price, None);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut state = serde_json::from_value::<LevelOneEquity>(json!({
+            "key": "AAPL",
+            "1": 189.5,
+            "2": 189.52,
+            "3": 189.51
+        }))
+        .unwrap();
+        let partial = serde_json::from_value::<LevelOneEquity>(json!({
+            "key": "AAPL",
+            "2": 189.55,
+            "8": 100
+        }))
+        .unwrap();
+
+        state.update(partial);
+        assert_eq!(state.bid_price, Some(price(189.5)));
+        assert_eq!(state.ask_price, Some(price(189.55)));
+        assert_eq!(state.last_price, Some(price(189.51)));
+        assert_eq!(state.total_volume, Some(100));
+    }
+
+    #[test]
+    fn test_field_id() {
+        assert_eq!(LevelOneEquityField::Symbol.id(), 0);
+        assert_eq!(LevelOneEquityField::BidPrice.id(), 1);
+        assert_eq!(LevelOneEquityField::PostMarketPercentChange.id(), 51);
+    }
+}
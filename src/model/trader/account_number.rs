@@ -10,6 +10,20 @@ pub struct AccountNumberHash {
     pub hash_value: String,
 }
 
+/// Lookup helpers for [`AccountNumbers`]
+pub trait AccountNumbersExt {
+    /// Get the hashed value of a plain account number, which other trader endpoints require.
+    fn hash_for(&self, account_number: &str) -> Option<&str>;
+}
+
+impl AccountNumbersExt for AccountNumbers {
+    fn hash_for(&self, account_number: &str) -> Option<&str> {
+        self.iter()
+            .find(|x| x.account_number == account_number)
+            .map(|x| x.hash_value.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,4 +39,21 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    fn test_hash_for() {
+        let account_numbers = vec![
+            AccountNumberHash {
+                account_number: "123".to_string(),
+                hash_value: "ABC".to_string(),
+            },
+            AccountNumberHash {
+                account_number: "456".to_string(),
+                hash_value: "DEF".to_string(),
+            },
+        ];
+
+        assert_eq!(account_numbers.hash_for("456"), Some("DEF"));
+        assert_eq!(account_numbers.hash_for("789"), None);
+    }
 }
@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::model::{to_price, Price};
+
 pub type Accounts = Vec<Account>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -16,6 +18,40 @@ pub enum SecuritiesAccount {
     Cash(Box<CashAccount>),
 }
 
+impl SecuritiesAccount {
+    #[must_use]
+    pub fn base(&self) -> &SecuritiesAccountBase {
+        match self {
+            SecuritiesAccount::Margin(x) => &x.securities_account_base,
+            SecuritiesAccount::Cash(x) => &x.securities_account_base,
+        }
+    }
+
+    /// Empty if the account was requested without [`crate::api::parameter::AccountField::Positions`]
+    #[must_use]
+    pub fn positions(&self) -> &[Position] {
+        self.base().positions.as_deref().unwrap_or_default()
+    }
+
+    /// Sum of [`Position::market_value`], short positions count negative
+    #[must_use]
+    pub fn market_value(&self) -> Price {
+        self.positions().iter().map(|x| x.market_value).sum()
+    }
+
+    /// Sum of [`Position::unrealized_pnl`]
+    #[must_use]
+    pub fn unrealized_pnl(&self) -> Price {
+        self.positions().iter().map(Position::unrealized_pnl).sum()
+    }
+
+    /// Sum of [`Position::day_pnl`]
+    #[must_use]
+    pub fn day_pnl(&self) -> f64 {
+        self.positions().iter().map(Position::day_pnl).sum()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SecuritiesAccountBase {
@@ -44,64 +80,64 @@ pub struct MarginAccount {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginInitialBalance {
-    pub accrued_interest: f64,
-    pub available_funds_non_marginable_trade: f64,
-    pub bond_value: f64,
-    pub buying_power: f64,
-    pub cash_balance: f64,
-    pub cash_available_for_trading: f64,
-    pub cash_receipts: f64,
-    pub day_trading_buying_power: f64,
-    pub day_trading_buying_power_call: f64,
-    pub day_trading_equity_call: f64,
-    pub equity: f64,
+    pub accrued_interest: Price,
+    pub available_funds_non_marginable_trade: Price,
+    pub bond_value: Price,
+    pub buying_power: Price,
+    pub cash_balance: Price,
+    pub cash_available_for_trading: Price,
+    pub cash_receipts: Price,
+    pub day_trading_buying_power: Price,
+    pub day_trading_buying_power_call: Price,
+    pub day_trading_equity_call: Price,
+    pub equity: Price,
     pub equity_percentage: f64,
-    pub liquidation_value: f64,
-    pub long_margin_value: f64,
-    pub long_option_market_value: f64,
-    pub long_stock_value: f64,
-    pub maintenance_call: f64,
-    pub maintenance_requirement: f64,
-    pub margin: f64,
-    pub margin_equity: f64,
-    pub money_market_fund: f64,
-    pub mutual_fund_value: f64,
-    pub reg_tcall: f64,
-    pub short_margin_value: f64,
-    pub short_option_market_value: f64,
-    pub short_stock_value: f64,
-    pub total_cash: f64,
+    pub liquidation_value: Price,
+    pub long_margin_value: Price,
+    pub long_option_market_value: Price,
+    pub long_stock_value: Price,
+    pub maintenance_call: Price,
+    pub maintenance_requirement: Price,
+    pub margin: Price,
+    pub margin_equity: Price,
+    pub money_market_fund: Price,
+    pub mutual_fund_value: Price,
+    pub reg_tcall: Price,
+    pub short_margin_value: Price,
+    pub short_option_market_value: Price,
+    pub short_stock_value: Price,
+    pub total_cash: Price,
     pub is_in_call: bool,
-    pub unsettled_cash: f64,
-    pub pending_deposits: f64,
-    pub margin_balance: f64,
-    pub short_balance: f64,
-    pub account_value: f64,
+    pub unsettled_cash: Price,
+    pub pending_deposits: Price,
+    pub margin_balance: Price,
+    pub short_balance: Price,
+    pub account_value: Price,
 }
 
 #[allow(clippy::struct_field_names)]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginBalance {
-    pub available_funds: f64,
-    pub available_funds_non_marginable_trade: f64,
-    pub buying_power: f64,
-    pub buying_power_non_marginable_trade: f64,
-    pub day_trading_buying_power: f64,
-    pub day_trading_buying_power_call: f64,
-    pub equity: f64,
+    pub available_funds: Price,
+    pub available_funds_non_marginable_trade: Price,
+    pub buying_power: Price,
+    pub buying_power_non_marginable_trade: Price,
+    pub day_trading_buying_power: Price,
+    pub day_trading_buying_power_call: Price,
+    pub equity: Price,
     pub equity_percentage: f64,
-    pub long_margin_value: f64,
-    pub maintenance_call: f64,
-    pub maintenance_requirement: f64,
-    pub margin_balance: f64,
-    pub reg_tcall: f64,
-    pub short_balance: f64,
-    pub short_margin_value: f64,
-    pub sma: f64,
+    pub long_margin_value: Price,
+    pub maintenance_call: Price,
+    pub maintenance_requirement: Price,
+    pub margin_balance: Price,
+    pub reg_tcall: Price,
+    pub short_balance: Price,
+    pub short_margin_value: Price,
+    pub sma: Price,
     pub is_in_call: bool,
-    pub stock_buying_power: f64,
-    pub option_buying_power: f64,
+    pub stock_buying_power: Price,
+    pub option_buying_power: Price,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -118,59 +154,59 @@ pub struct CashAccount {
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CashInitialBalance {
-    pub accrued_interest: f64,
-    pub cash_available_for_trading: f64,
-    pub cash_available_for_withdrawal: f64,
-    pub cash_balance: f64,
-    pub bond_value: f64,
-    pub cash_receipts: f64,
-    pub liquidation_value: f64,
-    pub long_option_market_value: f64,
-    pub long_stock_value: f64,
-    pub money_market_fund: f64,
-    pub mutual_fund_value: f64,
-    pub short_option_market_value: f64,
-    pub short_stock_value: f64,
+    pub accrued_interest: Price,
+    pub cash_available_for_trading: Price,
+    pub cash_available_for_withdrawal: Price,
+    pub cash_balance: Price,
+    pub bond_value: Price,
+    pub cash_receipts: Price,
+    pub liquidation_value: Price,
+    pub long_option_market_value: Price,
+    pub long_stock_value: Price,
+    pub money_market_fund: Price,
+    pub mutual_fund_value: Price,
+    pub short_option_market_value: Price,
+    pub short_stock_value: Price,
     pub is_in_call: bool,
-    pub unsettled_cash: f64,
-    pub cash_debit_call_value: f64,
-    pub pending_deposits: f64,
-    pub account_value: f64,
+    pub unsettled_cash: Price,
+    pub cash_debit_call_value: Price,
+    pub pending_deposits: Price,
+    pub account_value: Price,
 }
 
 #[allow(clippy::struct_field_names)]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CashBalance {
-    pub cash_available_for_trading: f64,
-    pub cash_available_for_withdrawal: f64,
-    pub cash_call: Option<f64>,
-    pub long_non_marginable_market_value: Option<f64>,
-    pub total_cash: Option<f64>,
-    pub cash_debit_call_value: Option<f64>,
-    pub unsettled_cash: Option<f64>,
+    pub cash_available_for_trading: Price,
+    pub cash_available_for_withdrawal: Price,
+    pub cash_call: Option<Price>,
+    pub long_non_marginable_market_value: Option<Price>,
+    pub total_cash: Option<Price>,
+    pub cash_debit_call_value: Option<Price>,
+    pub unsettled_cash: Option<Price>,
 
     // not in schema
-    pub accrued_interest: Option<f64>,
-    pub cash_balance: Option<f64>,
-    pub cash_receipts: Option<f64>,
-    pub long_option_market_value: Option<f64>,
-    pub liquidation_value: Option<f64>,
-    pub long_market_value: Option<f64>,
-    pub money_market_fund: Option<f64>,
-    pub savings: Option<f64>,
-    pub short_market_value: Option<f64>,
-    pub pending_deposits: Option<f64>,
-    pub mutual_fund_value: Option<f64>,
-    pub bond_value: Option<f64>,
-    pub short_option_market_value: Option<f64>,
+    pub accrued_interest: Option<Price>,
+    pub cash_balance: Option<Price>,
+    pub cash_receipts: Option<Price>,
+    pub long_option_market_value: Option<Price>,
+    pub liquidation_value: Option<Price>,
+    pub long_market_value: Option<Price>,
+    pub money_market_fund: Option<Price>,
+    pub savings: Option<Price>,
+    pub short_market_value: Option<Price>,
+    pub pending_deposits: Option<Price>,
+    pub mutual_fund_value: Option<Price>,
+    pub bond_value: Option<Price>,
+    pub short_option_market_value: Option<Price>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
     pub short_quantity: f64,
-    pub average_price: f64,
+    pub average_price: Price,
     pub current_day_profit_loss: f64,
     pub current_day_profit_loss_percentage: i64,
     pub long_quantity: f64,
@@ -178,12 +214,12 @@ pub struct Position {
     pub settled_short_quantity: f64,
     pub aged_quantity: f64,
     pub instrument: AccountsInstrument,
-    pub market_value: f64,
+    pub market_value: Price,
     pub maintenance_requirement: f64,
-    pub average_long_price: f64,
-    pub average_short_price: f64,
-    pub tax_lot_average_long_price: f64,
-    pub tax_lot_average_short_price: f64,
+    pub average_long_price: Price,
+    pub average_short_price: Price,
+    pub tax_lot_average_long_price: Price,
+    pub tax_lot_average_short_price: Price,
     pub long_open_profit_loss: f64,
     pub short_open_profit_loss: f64,
     pub previous_session_long_quantity: i64,
@@ -191,6 +227,55 @@ pub struct Position {
     pub current_day_cost: f64,
 }
 
+impl Position {
+    /// Signed quantity, negative for a short position
+    #[must_use]
+    pub fn quantity(&self) -> f64 {
+        self.long_quantity - self.short_quantity
+    }
+
+    /// Units per quantity, e.g. 100 shares per standard option contract
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn multiplier(&self) -> f64 {
+        match &self.instrument {
+            AccountsInstrument::Option(x) if x.option_multiplier > 0 => x.option_multiplier as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Current price per unit, derived from `market_value`
+    ///
+    /// `None` if the position is flat.
+    #[must_use]
+    pub fn current_price(&self) -> Option<Price> {
+        let units = self.quantity() * self.multiplier();
+        (units != 0.0).then(|| self.market_value / to_price(units))
+    }
+
+    /// Cost of opening the position by the average long and short prices, negative for a short position
+    #[must_use]
+    pub fn cost_basis(&self) -> Price {
+        (to_price(self.long_quantity) * self.average_long_price
+            - to_price(self.short_quantity) * self.average_short_price)
+            * to_price(self.multiplier())
+    }
+
+    /// `market_value` less [`Self::cost_basis`]
+    ///
+    /// A short position gains when the price falls below the average short price.
+    #[must_use]
+    pub fn unrealized_pnl(&self) -> Price {
+        self.market_value - self.cost_basis()
+    }
+
+    /// Profit or loss since the previous close, as reported by Schwab
+    #[must_use]
+    pub fn day_pnl(&self) -> f64 {
+        self.current_day_profit_loss
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountsInstrument {
@@ -300,7 +385,7 @@ pub struct AccountsBaseInstrument {
     pub net_change: Option<f64>,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum APICurrencyType {
     #[default]
@@ -310,7 +395,7 @@ pub enum APICurrencyType {
     Jpy,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AssetType {
     #[default]
@@ -327,7 +412,7 @@ pub enum AssetType {
     CollectiveInvestment,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountCashEquivalentType {
     #[default]
@@ -337,7 +422,7 @@ pub enum AccountCashEquivalentType {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountOptionPullCall {
     #[default]
@@ -346,7 +431,7 @@ pub enum AccountOptionPullCall {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountOptionType {
     #[default]
@@ -360,6 +445,9 @@ pub enum AccountOptionType {
 mod tests {
     use super::*;
 
+    use crate::model::price;
+    use float_cmp::assert_approx_eq;
+
     #[test]
     fn test_de_account() {
         let json = include_str!(concat!(
@@ -384,6 +472,90 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_de_balance_exact() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        ));
+        let account = serde_json::from_str::<Account>(json).unwrap();
+        let SecuritiesAccount::Cash(cash) = account.securities_account else {
+            panic!("should be a cash account");
+        };
+        let initial = cash.initial_balances.unwrap();
+        assert_eq!(
+            initial.cash_available_for_trading,
+            crate::model::price(12.34)
+        );
+        assert_eq!(initial.account_value, crate::model::price(12.34));
+
+        // amounts sent as strings are accepted as well
+        #[cfg(feature = "decimal")]
+        {
+            let balance = serde_json::from_value::<CashBalance>(serde_json::json!({
+                "cashAvailableForTrading": "1234567.89",
+                "cashAvailableForWithdrawal": 0.1,
+            }))
+            .unwrap();
+            assert_eq!(balance.cash_available_for_trading.to_string(), "1234567.89");
+            assert_eq!(balance.cash_available_for_withdrawal.to_string(), "0.1");
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_pnl() {
+        // 10 shares bought at 100, now 110
+        let long = Position {
+            long_quantity: 10.0,
+            average_long_price: price(100.0),
+            market_value: price(1100.0),
+            current_day_profit_loss: 20.0,
+            ..Default::default()
+        };
+        assert_approx_eq!(f64, long.quantity(), 10.0);
+        assert_eq!(long.current_price(), Some(price(110.0)));
+        assert_eq!(long.cost_basis(), price(1000.0));
+        assert_eq!(long.unrealized_pnl(), price(100.0));
+
+        // 2 contracts sold at 3.5, now 2.5
+        let short = Position {
+            short_quantity: 2.0,
+            average_short_price: price(3.5),
+            market_value: price(-500.0),
+            current_day_profit_loss: -30.0,
+            instrument: AccountsInstrument::Option(AccountOption {
+                option_multiplier: 100,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_approx_eq!(f64, short.quantity(), -2.0);
+        assert_eq!(short.current_price(), Some(price(2.5)));
+        assert_eq!(short.cost_basis(), price(-700.0));
+        assert_eq!(short.unrealized_pnl(), price(200.0));
+
+        assert_eq!(Position::default().current_price(), None);
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        ));
+        let mut account = serde_json::from_str::<Account>(json).unwrap();
+        assert!(account.securities_account.positions().is_empty());
+        assert_eq!(account.securities_account.unrealized_pnl(), price(0.0));
+
+        let SecuritiesAccount::Cash(ref mut cash) = account.securities_account else {
+            panic!("should be a cash account");
+        };
+        cash.securities_account_base.positions = Some(vec![long, short]);
+        let account = account.securities_account;
+        assert_eq!(account.market_value(), price(600.0));
+        assert_eq!(account.unrealized_pnl(), price(300.0));
+        assert_approx_eq!(f64, account.day_pnl(), -10.0);
+    }
+
     #[test]
     fn test_de_accounts2() {
         let json = include_str!(concat!(
@@ -2,6 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::model::trader::accounts::AccountsInstrument;
+use crate::model::Price;
 
 use super::preview_order::Instruction;
 
@@ -20,18 +21,18 @@ pub struct Order {
     pub requested_destination: RequestedDestination,
     pub destination_link_name: String,
     pub release_time: Option<chrono::DateTime<chrono::Utc>>,
-    pub stop_price: Option<f64>,
+    pub stop_price: Option<Price>,
     pub stop_price_link_basis: Option<StopPriceLinkBasis>,
     pub stop_price_link_type: Option<StopPriceLinkType>,
-    pub stop_price_offset: Option<f64>,
+    pub stop_price_offset: Option<Price>,
     pub stop_type: Option<StopType>,
     pub price_link_basis: Option<PriceLinkBasis>,
     pub price_link_type: Option<PriceLinkType>,
-    pub price: f64,
+    pub price: Price,
     pub tax_lot_method: Option<TaxLotMethod>,
     /// xml: `OrderedMap` { "name": "orderLegCollection", "wrapped": true }
     pub order_leg_collection: Vec<OrderLegCollection>,
-    pub activation_price: Option<f64>,
+    pub activation_price: Option<Price>,
     pub special_instruction: Option<SpecialInstruction>,
     pub order_strategy_type: OrderStrategyType,
     pub order_id: i64,
@@ -39,7 +40,7 @@ pub struct Order {
     pub cancelable: bool,
     /// default: false
     pub editable: bool,
-    pub status: Status,
+    pub status: OrderStatus,
     pub entered_time: chrono::DateTime<chrono::Utc>,
     pub close_time: Option<chrono::DateTime<chrono::Utc>>,
     pub tag: Option<String>,
@@ -53,6 +54,26 @@ pub struct Order {
     pub status_description: Option<String>,
 }
 
+impl Order {
+    /// See [`OrderStatus::is_terminal`].
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_terminal()
+    }
+
+    /// See [`OrderStatus::is_open`].
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.status.is_open()
+    }
+
+    /// See [`OrderStatus::is_filled`].
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        self.status.is_filled()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderLegCollection {
@@ -82,7 +103,7 @@ pub struct OrderActivity {
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionLeg {
     pub leg_id: i64,
-    pub price: f64,
+    pub price: Price,
     pub quantity: f64,
     pub mismarked_quantity: f64,
     pub instrument_id: i64,
@@ -90,7 +111,7 @@ pub struct ExecutionLeg {
 }
 
 /// The market session during which the order trade should be executed.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Session {
     /// Normal market hours, from 9:30am to 4:00pm Eastern.
@@ -103,10 +124,13 @@ pub enum Session {
     /// Orders are active during all trading sessions except the overnight
     /// session. This is the union of ``NORMAL``, ``AM``, and ``PM``.
     Seamless,
+    /// Any value not documented by Schwab
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Length of time over which the trade will be active.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Duration {
     /// Cancel the trade at the end of the trading day. Note if the order cannot
@@ -125,10 +149,13 @@ pub enum Duration {
     EndOfMonth,
     NextEndOfMonth,
     Unknown,
+    /// Any value not documented by Schwab
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Type of order to place.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderType {
     #[default]
@@ -175,10 +202,13 @@ pub enum OrderType {
     NetZero,
     LimitOnClose,
     Unknown,
+    /// Any value not documented by Schwab
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Explicit order strategies for executing multi-leg options orders.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ComplexOrderStrategyType {
     #[default]
@@ -223,7 +253,7 @@ pub enum ComplexOrderStrategyType {
 }
 
 /// Destinations for when you want to request a specific destination for your order.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RequestedDestination {
     #[default]
@@ -241,7 +271,7 @@ pub enum RequestedDestination {
     Auto,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StopPriceLinkBasis {
     #[default]
@@ -256,7 +286,7 @@ pub enum StopPriceLinkBasis {
     Average,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StopPriceLinkType {
     #[default]
@@ -265,7 +295,7 @@ pub enum StopPriceLinkType {
     Tick,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StopType {
     #[default]
@@ -276,7 +306,7 @@ pub enum StopType {
     Mark,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PriceLinkBasis {
     #[default]
@@ -291,7 +321,7 @@ pub enum PriceLinkBasis {
     Average,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PriceLinkType {
     #[default]
@@ -300,7 +330,7 @@ pub enum PriceLinkType {
     Tick,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaxLotMethod {
     #[default]
@@ -314,7 +344,7 @@ pub enum TaxLotMethod {
 }
 
 /// Special instruction for trades.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SpecialInstruction {
     #[default]
@@ -329,7 +359,7 @@ pub enum SpecialInstruction {
 }
 
 /// Rules for composite orders.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStrategyType {
     /// No chaining, only a single order is submitted
@@ -347,9 +377,10 @@ pub enum OrderStrategyType {
     Trigger,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Lifecycle state of an order
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Status {
+pub enum OrderStatus {
     #[default]
     AwaitingParentOrder,
     AwaitingCondition,
@@ -372,9 +403,41 @@ pub enum Status {
     PendingAcknowledgement,
     PendingRecall,
     Unknown,
+    /// Any value not documented by Schwab
+    #[serde(untagged)]
+    Other(String),
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Renamed to [`OrderStatus`]
+#[deprecated(note = "renamed to `OrderStatus`")]
+pub type Status = OrderStatus;
+
+impl OrderStatus {
+    /// The order has reached a final state and will not change anymore.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Rejected | Self::Canceled | Self::Replaced | Self::Filled | Self::Expired
+        )
+    }
+
+    /// The order is still live at Schwab, either waiting or working.
+    ///
+    /// `Unknown` and undocumented statuses are neither open nor terminal.
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        !self.is_terminal() && !matches!(self, Self::Unknown | Self::Other(_))
+    }
+
+    /// The order has been completely filled.
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        matches!(self, Self::Filled)
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ActivityType {
     #[default]
@@ -383,7 +446,7 @@ pub enum ActivityType {
 }
 
 /// Order Status
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExecutionType {
     #[default]
@@ -403,7 +466,7 @@ pub enum ExecutionType {
     Canceled,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderLegType {
     #[default]
@@ -417,7 +480,7 @@ pub enum OrderLegType {
     CollectiveInvestment,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PositionEffect {
     #[default]
@@ -426,7 +489,7 @@ pub enum PositionEffect {
     Automatic,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QuantityType {
     #[default]
@@ -435,7 +498,7 @@ pub enum QuantityType {
     Shares,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DivCapGains {
     #[default]
@@ -447,6 +510,8 @@ pub enum DivCapGains {
 mod tests {
     use super::*;
 
+    use float_cmp::assert_approx_eq;
+
     #[test]
     fn test_de_order() {
         let json = include_str!(concat!(
@@ -459,6 +524,83 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_order_status() {
+        let status = serde_json::from_str::<OrderStatus>(r#""WORKING""#).unwrap();
+        assert_eq!(status, OrderStatus::Working);
+        #[allow(deprecated)]
+        let _: Status = status.clone();
+        assert!(status.is_open());
+        assert!(!status.is_terminal());
+        assert!(!status.is_filled());
+
+        let status = serde_json::from_str::<OrderStatus>(r#""AWAITING_PARENT_ORDER""#).unwrap();
+        assert_eq!(status, OrderStatus::AwaitingParentOrder);
+        assert!(status.is_open());
+
+        let status = serde_json::from_str::<OrderStatus>(r#""FILLED""#).unwrap();
+        assert!(status.is_terminal());
+        assert!(status.is_filled());
+        assert!(!status.is_open());
+
+        for json in [
+            r#""CANCELED""#,
+            r#""REJECTED""#,
+            r#""EXPIRED""#,
+            r#""REPLACED""#,
+        ] {
+            let status = serde_json::from_str::<OrderStatus>(json).unwrap();
+            assert!(status.is_terminal());
+            assert!(!status.is_filled());
+        }
+
+        let status = serde_json::from_str::<OrderStatus>(r#""SOMETHING_NEW""#).unwrap();
+        assert_eq!(status, OrderStatus::Other("SOMETHING_NEW".to_string()));
+        assert!(!status.is_open());
+        assert!(!status.is_terminal());
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#""SOMETHING_NEW""#
+        );
+    }
+
+    #[test]
+    fn test_serde_other() {
+        assert_eq!(
+            serde_json::from_str::<OrderType>(r#""STOP_LIMIT""#).unwrap(),
+            OrderType::StopLimit
+        );
+        assert_eq!(
+            serde_json::from_str::<Session>(r#""SEAMLESS""#).unwrap(),
+            Session::Seamless
+        );
+        assert_eq!(
+            serde_json::from_str::<Duration>(r#""GOOD_TILL_CANCEL""#).unwrap(),
+            Duration::GoodTillCancel
+        );
+
+        // unknown values are kept as is
+        let val = serde_json::from_str::<OrderType>(r#""MARKT""#).unwrap();
+        assert_eq!(val, OrderType::Other("MARKT".to_string()));
+        assert_eq!(serde_json::to_string(&val).unwrap(), r#""MARKT""#);
+        assert_eq!(
+            serde_json::from_str::<Session>(r#""OVERNIGHT""#).unwrap(),
+            Session::Other("OVERNIGHT".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<Duration>(r#""GOOD_TILL_DATE""#).unwrap(),
+            Duration::Other("GOOD_TILL_DATE".to_string())
+        );
+
+        // but an order to place only takes documented order types
+        assert!(
+            serde_json::from_str::<crate::model::trader::order_request::OrderTypeRequest>(
+                r#""MARKT""#
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_de_order_real() {
         let json = include_str!(concat!(
@@ -494,4 +636,31 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn test_de_orders_real_execution_legs() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Orders_real.json"
+        ));
+
+        let val = serde_json::from_str::<Vec<Order>>(json).unwrap();
+        let activity = &val[0]
+            .order_activity_collection
+            .as_ref()
+            .expect("order activity")[0];
+        assert_eq!(activity.activity_type, ActivityType::Execution);
+        let leg = activity.execution_legs[0];
+        assert_eq!(leg.leg_id, 1);
+        assert_eq!(leg.instrument_id, 3_148_252);
+        assert_eq!(leg.price, crate::model::price(48.72));
+        assert_approx_eq!(f64, leg.quantity, 10.0);
+        assert_eq!(
+            leg.time,
+            "2024-06-06T13:46:03Z"
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        );
+    }
 }
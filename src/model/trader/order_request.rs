@@ -3,32 +3,36 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_with::skip_serializing_none;
 
-use super::accounts::AccountsInstrument;
+use crate::api::parameter::OptionSymbol;
+use crate::model::Price;
+
+use super::accounts::{AccountOptionPullCall, AccountsInstrument};
 use super::order::ComplexOrderStrategyType;
 use super::order::Duration;
 use super::order::Order;
 use super::order::OrderActivity;
 use super::order::OrderLegCollection;
+use super::order::OrderStatus;
 use super::order::OrderStrategyType;
 use super::order::OrderType;
 use super::order::PriceLinkBasis;
 use super::order::PriceLinkType;
 use super::order::Session;
 use super::order::SpecialInstruction;
-use super::order::Status;
 use super::order::StopPriceLinkBasis;
 use super::order::StopPriceLinkType;
 use super::order::StopType;
 use super::order::TaxLotMethod;
 use super::preview_order::Instruction;
 use crate::model::market_data::instrument::InstrumentAssetType;
+use crate::model::market_data::option_chain::PutCall;
 use crate::model::InstrumentResponse;
 use crate::Error;
 
 /// More Info in [Charles Schwab Developer Portal](https://developer.schwab.com/) : API Products -> Trader API - Individual -> Accounts and Trading Production -> Documentation -> Place Order Samples
 #[skip_serializing_none]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
-#[builder(setter(strip_option), default)]
+#[builder(setter(strip_option), default, build_fn(validate = "Self::validate"))]
 #[serde(rename_all = "camelCase")]
 pub struct OrderRequest {
     pub session: Option<Session>,
@@ -41,18 +45,18 @@ pub struct OrderRequest {
     pub remaining_quantity: Option<f64>,
     pub destination_link_name: Option<String>,
     pub release_time: Option<chrono::DateTime<chrono::Utc>>,
-    pub stop_price: Option<f64>,
+    pub stop_price: Option<Price>,
     pub stop_price_link_basis: Option<StopPriceLinkBasis>,
     pub stop_price_link_type: Option<StopPriceLinkType>,
-    pub stop_price_offset: Option<f64>,
+    pub stop_price_offset: Option<Price>,
     pub stop_type: Option<StopType>,
     pub price_link_basis: Option<PriceLinkBasis>,
     pub price_link_type: Option<PriceLinkType>,
-    pub price: Option<f64>,
+    pub price: Option<Price>,
     pub tax_lot_method: Option<TaxLotMethod>,
-    /// xml: OrderedMap { "name": "orderLegCollection", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "orderLegCollection", "wrapped": true }
     pub order_leg_collection: Option<Vec<OrderLegCollectionRequest>>,
-    pub activation_price: Option<f64>,
+    pub activation_price: Option<Price>,
     pub special_instruction: Option<SpecialInstruction>,
     pub order_strategy_type: OrderStrategyType,
     pub order_id: Option<i64>,
@@ -60,15 +64,15 @@ pub struct OrderRequest {
     pub cancelable: Option<bool>,
     /// default: false
     pub editable: Option<bool>,
-    pub status: Option<Status>,
+    pub status: Option<OrderStatus>,
     pub entered_time: Option<chrono::DateTime<chrono::Utc>>,
     pub close_time: Option<chrono::DateTime<chrono::Utc>>,
     pub account_number: Option<i64>,
-    /// xml: OrderedMap { "name": "orderActivity", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "orderActivity", "wrapped": true }
     pub order_activity_collection: Option<Vec<OrderActivity>>,
-    /// xml: OrderedMap { "name": "replacingOrder", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "replacingOrder", "wrapped": true }
     pub replacing_order_collection: Option<Vec<String>>,
-    /// xml: OrderedMap { "name": "childOrder", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "childOrder", "wrapped": true }
     pub child_order_strategies: Option<Vec<OrderRequest>>,
     pub status_description: Option<String>,
 }
@@ -149,7 +153,7 @@ impl OrderRequest {
         symbol: InstrumentRequest,
         instruction: Instruction,
         quantity: f64,
-        price: f64,
+        price: Price,
     ) -> Result<Self, Error> {
         let order_leg_collection = vec![OrderLegCollectionRequest {
             instruction,
@@ -167,11 +171,160 @@ impl OrderRequest {
             .build()
             .map_err(Error::OrderRequestBuild)
     }
+
+    /// Buy `quantity` shares of `symbol` at the market, good for the day.
+    pub fn buy_equity(symbol: impl Into<String>, quantity: f64) -> Result<Self, Error> {
+        Self::market(
+            InstrumentRequest::equity(symbol),
+            Instruction::Buy,
+            quantity,
+        )
+    }
+
+    /// Buy a fractional number of `shares` of `symbol` at the market, good for the day,
+    /// e.g. `2.5`, which Schwab only accepts for eligible equities.
+    pub fn buy_fractional(symbol: impl Into<String>, shares: f64) -> Result<Self, Error> {
+        Self::buy_equity(symbol, shares)
+    }
+
+    /// Sell to open `quantity` contracts of the OSI `symbol` at a limit of `price`, good for the day.
+    pub fn sell_to_open_option(
+        symbol: impl Into<String>,
+        quantity: f64,
+        price: Price,
+    ) -> Result<Self, Error> {
+        Self::limit(
+            InstrumentRequest::option(symbol),
+            Instruction::SellToOpen,
+            quantity,
+            price,
+        )
+    }
+
+    /// Create a stop order, which places a market order once the price reaches `stop_price`.
+    pub fn stop(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        stop_price: Price,
+    ) -> Result<Self, Error> {
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction,
+            quantity,
+            instrument: symbol,
+        }];
+        OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Stop)
+            .session(Session::Normal)
+            .stop_price(stop_price)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a trailing stop order selling `quantity` shares of `symbol`, good for the day.
+    ///
+    /// The stop trails the bid by `offset`, in dollars or percent as of `offset_type`,
+    /// and places a market order once the price falls back to it.
+    pub fn trailing_stop(
+        symbol: impl Into<String>,
+        quantity: f64,
+        offset: Price,
+        offset_type: StopPriceLinkType,
+    ) -> Result<Self, Error> {
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction: Instruction::Sell,
+            quantity,
+            instrument: InstrumentRequest::equity(symbol),
+        }];
+        OrderRequestBuilder::default()
+            .complex_order_strategy_type(ComplexOrderStrategyType::None)
+            .order_type(OrderTypeRequest::TrailingStop)
+            .session(Session::Normal)
+            .stop_price_link_basis(StopPriceLinkBasis::Bid)
+            .stop_price_link_type(offset_type)
+            .stop_price_offset(offset)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// One Cancels Another
+    ///
+    /// The orders are sent at the same time, once one of them fills the others are cancelled.
+    #[must_use]
+    pub fn oco(orders: Vec<OrderRequest>) -> Self {
+        Self {
+            order_strategy_type: OrderStrategyType::Oco,
+            child_order_strategies: Some(orders),
+            ..Default::default()
+        }
+    }
+
+    /// One Triggers Another
+    ///
+    /// `child` is sent once this order fills, e.g. an [`OrderRequest::oco`].
+    #[must_use]
+    pub fn trigger(mut self, child: OrderRequest) -> Self {
+        self.order_strategy_type = OrderStrategyType::Trigger;
+        self.child_order_strategies
+            .get_or_insert_with(Vec::new)
+            .push(child);
+        self
+    }
+
+    /// Bracket order
+    ///
+    /// Once `entry` fills, `take_profit` and `stop_loss` are sent as [`OrderRequest::oco`].
+    #[must_use]
+    pub fn bracket(
+        entry: OrderRequest,
+        take_profit: OrderRequest,
+        stop_loss: OrderRequest,
+    ) -> Self {
+        entry.trigger(Self::oco(vec![take_profit, stop_loss]))
+    }
+}
+
+impl OrderRequestBuilder {
+    /// Leg quantities must be positive, and the stop price link fields only apply to trailing stops.
+    fn validate(&self) -> Result<(), String> {
+        if let Some(Some(legs)) = &self.order_leg_collection {
+            if let Some(leg) = legs
+                .iter()
+                .find(|x| x.quantity.is_nan() || x.quantity <= 0.0)
+            {
+                return Err(format!("quantity must be positive, not {}", leg.quantity));
+            }
+        }
+
+        let trailing = self.stop_price_link_basis.flatten().is_some()
+            || self.stop_price_link_type.flatten().is_some()
+            || self.stop_price_offset.flatten().is_some();
+        if trailing
+            && !matches!(
+                self.order_type,
+                Some(Some(
+                    OrderTypeRequest::TrailingStop | OrderTypeRequest::TrailingStopLimit
+                ))
+            )
+        {
+            return Err(format!(
+                "stopPriceLinkBasis, stopPriceLinkType and stopPriceOffset require a trailing stop, not {:?}",
+                self.order_type.flatten()
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// Same as `super::order::OrderType`, but does not have UNKNOWN since this type is not allowed as an input
 /// Type of order to place.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderTypeRequest {
     /// Execute the order immediately at the best-available price.
@@ -219,6 +372,9 @@ pub enum OrderTypeRequest {
     LimitOnClose,
 }
 
+/// # Panics
+///
+/// Will panic on [`OrderType::Unknown`] and [`OrderType::Other`], which are not allowed as an input
 impl From<OrderType> for OrderTypeRequest {
     fn from(value: OrderType) -> Self {
         match value {
@@ -237,6 +393,7 @@ impl From<OrderType> for OrderTypeRequest {
             OrderType::NetZero => OrderTypeRequest::NetZero,
             OrderType::LimitOnClose => OrderTypeRequest::LimitOnClose,
             OrderType::Unknown => panic!("Unknown"),
+            OrderType::Other(x) => panic!("Other({x})"),
         }
     }
 }
@@ -265,8 +422,32 @@ impl From<OrderLegCollection> for OrderLegCollectionRequest {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum InstrumentRequest {
-    Equity { symbol: String },
-    Option { symbol: String },
+    Equity {
+        symbol: String,
+    },
+    Option {
+        /// OSI symbol, e.g. `XYZ   240315C00500000`
+        symbol: String,
+        #[serde(rename = "putCall", default, skip_serializing_if = "Option::is_none")]
+        put_call: Option<PutCall>,
+    },
+}
+
+impl InstrumentRequest {
+    #[must_use]
+    pub fn equity(symbol: impl Into<String>) -> Self {
+        Self::Equity {
+            symbol: symbol.into(),
+        }
+    }
+
+    /// `put_call` is taken from the OSI symbol by [`OptionSymbol::parse`], or `None` if it isn't one.
+    #[must_use]
+    pub fn option(symbol: impl Into<String>) -> Self {
+        let symbol = symbol.into();
+        let put_call = OptionSymbol::parse(&symbol).ok().map(|x| x.put_call());
+        Self::Option { symbol, put_call }
+    }
 }
 
 impl From<AccountsInstrument> for InstrumentRequest {
@@ -286,6 +467,11 @@ impl From<AccountsInstrument> for InstrumentRequest {
             },
             AccountsInstrument::Option(x) => Self::Option {
                 symbol: x.accounts_base_instrument.symbol,
+                put_call: match x.put_call {
+                    AccountOptionPullCall::Put => Some(PutCall::Put),
+                    AccountOptionPullCall::Call => Some(PutCall::Call),
+                    AccountOptionPullCall::Unknown => None,
+                },
             },
             AccountsInstrument::Index(x) => Self::Equity {
                 symbol: x.accounts_base_instrument.symbol,
@@ -316,9 +502,9 @@ impl From<InstrumentResponse> for InstrumentRequest {
             | InstrumentAssetType::Unknown => Self::Equity {
                 symbol: value.symbol,
             },
-            InstrumentAssetType::FutureOption | InstrumentAssetType::Option => Self::Option {
-                symbol: value.symbol,
-            },
+            InstrumentAssetType::FutureOption | InstrumentAssetType::Option => {
+                Self::option(value.symbol)
+            }
         }
     }
 }
@@ -326,6 +512,7 @@ impl From<InstrumentResponse> for InstrumentRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::price;
 
     use assert_json_diff::{assert_json_matches, CompareMode, Config, NumericMode};
     use serde_json::json;
@@ -375,6 +562,178 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serde_instrument() {
+        let equity = InstrumentRequest::equity("XYZ");
+        let json = json!({ "assetType": "EQUITY", "symbol": "XYZ" });
+        assert_eq!(serde_json::to_value(&equity).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<InstrumentRequest>(json).unwrap(),
+            equity
+        );
+
+        let option = InstrumentRequest::option("XYZ   240315C00500000");
+        let json = json!({
+            "assetType": "OPTION",
+            "symbol": "XYZ   240315C00500000",
+            "putCall": "CALL"
+        });
+        assert_eq!(serde_json::to_value(&option).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<InstrumentRequest>(json).unwrap(),
+            option
+        );
+        assert_eq!(
+            InstrumentRequest::option("XYZ   240315P00045000"),
+            InstrumentRequest::Option {
+                symbol: "XYZ   240315P00045000".to_string(),
+                put_call: Some(PutCall::Put)
+            }
+        );
+
+        // as in the Schwab samples, without putCall
+        let json = json!({ "assetType": "OPTION", "symbol": "XYZ" });
+        assert_eq!(
+            serde_json::from_value::<InstrumentRequest>(json.clone()).unwrap(),
+            InstrumentRequest::option("XYZ")
+        );
+        assert_eq!(
+            serde_json::to_value(InstrumentRequest::option("XYZ")).unwrap(),
+            json
+        );
+    }
+
+    #[test]
+    fn test_buy_equity() {
+        assert_eq!(
+            OrderRequest::buy_equity("XYZ", 15.0).unwrap(),
+            OrderRequest::market(InstrumentRequest::equity("XYZ"), Instruction::Buy, 15.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_buy_fractional() {
+        let expected = json!({
+            "orderType": "MARKET",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY",
+                    "quantity": 2.5,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let order_req = OrderRequest::buy_fractional("XYZ", 2.5).unwrap();
+        assert_eq!(serde_json::to_value(order_req).unwrap(), expected);
+
+        for shares in [-1.0, 0.0, f64::NAN] {
+            let result = OrderRequest::buy_fractional("XYZ", shares);
+            assert!(
+                matches!(
+                    result,
+                    Err(Error::OrderRequestBuild(
+                        OrderRequestBuilderError::ValidationError(_)
+                    ))
+                ),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sell_to_open_option() {
+        // Sell to open 10 contracts of the XYZ March 15, 2024 $50 CALL at a Limit of $6.45 good for the Day.
+        let expected = json!({
+            "complexOrderStrategyType": "NONE",
+            "orderType": "LIMIT",
+            "session": "NORMAL",
+            "price": 6.45,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL_TO_OPEN",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ   240315C00500000",
+                        "assetType": "OPTION",
+                        "putCall": "CALL"
+                    }
+                }
+            ]
+        });
+
+        let order_req =
+            OrderRequest::sell_to_open_option("XYZ   240315C00500000", 10.0, price(6.45)).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
+    #[test]
+    fn test_trailing_stop() {
+        // Sell Trailing Stop: Stock
+        // Sell 10 shares of XYZ with a Trailing Stop where the trail is a -$10 offset from the time the order is submitted.
+        let expected = json!({
+            "complexOrderStrategyType": "NONE",
+            "orderType": "TRAILING_STOP",
+            "session": "NORMAL",
+            "stopPriceLinkBasis": "BID",
+            "stopPriceLinkType": "VALUE",
+            "stopPriceOffset": 10,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let order_req =
+            OrderRequest::trailing_stop("XYZ", 10.0, price(10.0), StopPriceLinkType::Value)
+                .unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        let result = OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Stop)
+            .stop_price_offset(price(10.0))
+            .build();
+        assert!(
+            matches!(result, Err(OrderRequestBuilderError::ValidationError(_))),
+            "{result:?}"
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_price() {
+        let val = serde_json::from_str::<Price>("187.42").unwrap();
+        assert_eq!(val, "187.42".parse::<Price>().unwrap());
+        assert_eq!(serde_json::to_string(&val).unwrap(), "187.42");
+        assert_eq!(price(0.1) + price(0.2), price(0.3));
+    }
+
     #[test]
     fn test_limit() {
         // Buy Limit: Single Option
@@ -398,10 +757,9 @@ mod tests {
             ]
         });
 
-        let symbol = InstrumentRequest::Option {
-            symbol: "XYZ   240315C00500000".to_string(),
-        };
-        let order_req = OrderRequest::limit(symbol, Instruction::BuyToOpen, 10.0, 6.45).unwrap();
+        let symbol = InstrumentRequest::option("XYZ   240315C00500000");
+        let order_req =
+            OrderRequest::limit(symbol, Instruction::BuyToOpen, 10.0, price(6.45)).unwrap();
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
@@ -440,17 +798,13 @@ mod tests {
             ]
         });
 
-        let symbol1 = InstrumentRequest::Option {
-            symbol: "XYZ   240315P00045000".to_string(),
-        };
-        let symbol2 = InstrumentRequest::Option {
-            symbol: "XYZ   240315P00043000".to_string(),
-        };
+        let symbol1 = InstrumentRequest::option("XYZ   240315P00045000");
+        let symbol2 = InstrumentRequest::option("XYZ   240315P00043000");
         let order_req = OrderRequestBuilder::default()
             .order_type(OrderTypeRequest::NetDebit)
             .session(Session::Normal)
             .duration(Duration::Day)
-            .price(0.1)
+            .price(price(0.1))
             .order_leg_collection(vec![
                 OrderLegCollectionRequest {
                     instruction: Instruction::BuyToOpen,
@@ -522,7 +876,7 @@ mod tests {
             .order_type(OrderTypeRequest::Limit)
             .session(Session::Normal)
             .duration(Duration::Day)
-            .price(42.03)
+            .price(price(42.03))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 10.0,
@@ -534,16 +888,19 @@ mod tests {
             .order_type(OrderTypeRequest::Limit)
             .session(Session::Normal)
             .duration(Duration::Day)
-            .price(34.97)
+            .price(price(34.97))
             .order_strategy_type(OrderStrategyType::Trigger)
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Buy,
                 quantity: 10.0,
-                instrument: symbol,
+                instrument: symbol.clone(),
             }])
-            .child_order_strategies(vec![child_order_req])
+            .child_order_strategies(vec![child_order_req.clone()])
             .build()
             .unwrap();
+        let mut entry = OrderRequest::limit(symbol, Instruction::Buy, 10.0, price(34.97)).unwrap();
+        entry.complex_order_strategy_type = None;
+        assert_eq!(entry.trigger(child_order_req), order_req);
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
@@ -605,7 +962,7 @@ mod tests {
             .order_type(OrderTypeRequest::Limit)
             .session(Session::Normal)
             .duration(Duration::Day)
-            .price(45.97)
+            .price(price(45.97))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 2.0,
@@ -617,8 +974,8 @@ mod tests {
             .order_type(OrderTypeRequest::StopLimit)
             .session(Session::Normal)
             .duration(Duration::Day)
-            .price(37.00)
-            .stop_price(37.03)
+            .price(price(37.00))
+            .stop_price(price(37.03))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 2.0,
@@ -628,9 +985,13 @@ mod tests {
             .unwrap();
         let order_req = OrderRequestBuilder::default()
             .order_strategy_type(OrderStrategyType::Oco)
-            .child_order_strategies(vec![child_order_req1, child_order_req2])
+            .child_order_strategies(vec![child_order_req1.clone(), child_order_req2.clone()])
             .build()
             .unwrap();
+        assert_eq!(
+            OrderRequest::oco(vec![child_order_req1, child_order_req2]),
+            order_req
+        );
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
@@ -711,7 +1072,7 @@ mod tests {
             .order_type(OrderTypeRequest::Limit)
             .session(Session::Normal)
             .duration(Duration::GoodTillCancel)
-            .price(15.27)
+            .price(price(15.27))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 5.0,
@@ -723,7 +1084,7 @@ mod tests {
             .order_type(OrderTypeRequest::Stop)
             .session(Session::Normal)
             .duration(Duration::GoodTillCancel)
-            .stop_price(11.27)
+            .stop_price(price(11.27))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 5.0,
@@ -741,7 +1102,7 @@ mod tests {
             .session(Session::Normal)
             .duration(Duration::Day)
             .order_type(OrderTypeRequest::Limit)
-            .price(14.97)
+            .price(price(14.97))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Buy,
                 quantity: 5.0,
@@ -750,6 +1111,22 @@ mod tests {
             .child_order_strategies(vec![child_order_req])
             .build()
             .unwrap();
+
+        let mut entry =
+            OrderRequest::limit(symbol.clone(), Instruction::Buy, 5.0, price(14.97)).unwrap();
+        entry.complex_order_strategy_type = None;
+        let mut take_profit =
+            OrderRequest::limit(symbol.clone(), Instruction::Sell, 5.0, price(15.27)).unwrap();
+        take_profit.complex_order_strategy_type = None;
+        take_profit.duration = Some(Duration::GoodTillCancel);
+        let mut stop_loss =
+            OrderRequest::stop(symbol, Instruction::Sell, 5.0, price(11.27)).unwrap();
+        stop_loss.duration = Some(Duration::GoodTillCancel);
+        assert_eq!(
+            OrderRequest::bracket(entry, take_profit, stop_loss),
+            order_req
+        );
+
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
@@ -794,8 +1171,8 @@ mod tests {
             .duration(Duration::Day)
             .stop_price_link_basis(StopPriceLinkBasis::Bid)
             .stop_price_link_type(StopPriceLinkType::Value)
-            .stop_price_offset(10.0)
-            .price(14.97)
+            .stop_price_offset(price(10.0))
+            .price(price(14.97))
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 10.0,
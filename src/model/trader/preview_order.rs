@@ -7,9 +7,13 @@ use super::order::Duration;
 use super::order::OrderStrategyType;
 use super::order::OrderType;
 use super::order::Session;
+use crate::model::Price;
 
+/// The result of previewing an order
+///
+/// Schwab omits the parts that do not apply to the order, those are left at their default values.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct PreviewOrder {
     pub order_id: i64,
     pub order_strategy: OrderStrategy,
@@ -17,9 +21,41 @@ pub struct PreviewOrder {
     pub commission_and_fee: CommissionAndFee,
 }
 
+impl PreviewOrder {
+    /// Returns the estimated commission summed over all legs
+    #[must_use]
+    pub fn commission(&self) -> Price {
+        self.commission_and_fee
+            .commission
+            .commission_legs
+            .iter()
+            .flat_map(|x| &x.commission_values)
+            .map(|x| x.value)
+            .sum()
+    }
+
+    /// Returns the estimated fees summed over all legs
+    #[must_use]
+    pub fn fees(&self) -> Price {
+        self.commission_and_fee
+            .fee
+            .fee_legs
+            .iter()
+            .flat_map(|x| &x.fee_values)
+            .map(|x| x.value)
+            .sum()
+    }
+
+    /// Returns `true` if Schwab would reject the order
+    #[must_use]
+    pub fn is_rejected(&self) -> bool {
+        !self.order_validation_result.rejects.is_empty()
+    }
+}
+
 #[allow(clippy::struct_field_names)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct OrderStrategy {
     pub account_number: String,
     pub advanced_order_type: AdvancedOrderType,
@@ -36,7 +72,7 @@ pub struct OrderStrategy {
     pub filled_quantity: i64,
     pub order_type: OrderType,
     pub order_value: i64,
-    pub price: f64,
+    pub price: Price,
     pub quantity: i64,
     pub remaining_quantity: i64,
     pub sell_non_marginable_first: bool,
@@ -47,22 +83,22 @@ pub struct OrderStrategy {
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct OrderBalance {
-    pub order_value: f64,
-    pub projected_available_fund: f64,
-    pub projected_buying_power: f64,
-    pub projected_commission: f64,
+    pub order_value: Price,
+    pub projected_available_fund: Price,
+    pub projected_buying_power: Price,
+    pub projected_commission: Price,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct OrderLeg {
-    pub ask_price: f64,
-    pub bid_price: f64,
-    pub last_price: f64,
-    pub mark_price: f64,
-    pub projected_commission: f64,
+    pub ask_price: Price,
+    pub bid_price: Price,
+    pub last_price: Price,
+    pub mark_price: Price,
+    pub projected_commission: Price,
     pub quantity: f64,
     pub final_symbol: String,
     pub leg_id: i64,
@@ -71,7 +107,7 @@ pub struct OrderLeg {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct OrderValidationResult {
     pub alerts: Vec<OrderValidationDetail>,
     pub accepts: Vec<OrderValidationDetail>,
@@ -81,7 +117,7 @@ pub struct OrderValidationResult {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct OrderValidationDetail {
     pub validation_rule_name: String,
     pub message: String,
@@ -92,7 +128,7 @@ pub struct OrderValidationDetail {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct CommissionAndFee {
     pub commission: Commission,
     pub fee: Fees,
@@ -100,46 +136,46 @@ pub struct CommissionAndFee {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct Commission {
     pub commission_legs: Vec<CommissionLeg>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct CommissionLeg {
     pub commission_values: Vec<CommissionValue>,
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct CommissionValue {
-    pub value: f64,
+    pub value: Price,
     #[serde(rename = "type")]
     pub type_field: FeeType,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct Fees {
     pub fee_legs: Vec<FeeLeg>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct FeeLeg {
     pub fee_values: Vec<FeeValue>,
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct FeeValue {
-    pub value: f64,
+    pub value: Price,
     #[serde(rename = "type")]
     pub type_field: FeeType,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AmountIndicator {
     #[default]
@@ -150,7 +186,7 @@ pub enum AmountIndicator {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SettlementInstruction {
     #[default]
@@ -160,7 +196,7 @@ pub enum SettlementInstruction {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AdvancedOrderType {
     #[default]
@@ -175,7 +211,7 @@ pub enum AdvancedOrderType {
     Pair,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum APIOrderStatus {
     #[default]
@@ -204,7 +240,7 @@ pub enum APIOrderStatus {
 
 /// Instructions for opening and closing equity positions.
 /// Instructions for opening and closing options positions.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Instruction {
     #[default]
@@ -230,7 +266,7 @@ pub enum Instruction {
     SellShortExempt,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum APIRuleAction {
     #[default]
@@ -241,7 +277,7 @@ pub enum APIRuleAction {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FeeType {
     #[default]
@@ -276,6 +312,8 @@ pub enum FeeType {
 mod tests {
     use super::*;
 
+    use crate::model::price;
+
     #[test]
     fn test_de() {
         let json = include_str!(concat!(
@@ -287,4 +325,53 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_de_partial() {
+        let json = serde_json::json!({
+            "orderStrategy": {
+                "orderBalance": {
+                    "orderValue": 1_899.0,
+                    "projectedAvailableFund": 8_101.0,
+                    "projectedBuyingPower": 8_101.0,
+                    "projectedCommission": 0.0
+                },
+                "orderType": "LIMIT",
+                "price": 189.9,
+                "quantity": 10
+            },
+            "orderValidationResult": {
+                "warns": [{
+                    "validationRuleName": "NightTrading",
+                    "message": "Order will be placed for the next session"
+                }]
+            },
+            "commissionAndFee": {
+                "commission": {
+                    "commissionLegs": [{
+                        "commissionValues": [
+                            { "value": 0.65, "type": "COMMISSION" },
+                            { "value": 0.35, "type": "COMMISSION" }
+                        ]
+                    }]
+                },
+                "fee": {
+                    "feeLegs": [{
+                        "feeValues": [
+                            { "value": 0.01, "type": "SEC_FEE" },
+                            { "value": 0.02, "type": "OPT_REG_FEE" }
+                        ]
+                    }]
+                }
+            }
+        });
+
+        let val = serde_json::from_value::<PreviewOrder>(json).unwrap();
+        assert_eq!(val.order_strategy.order_balance.order_value, price(1_899.0));
+        assert_eq!(val.order_validation_result.warns.len(), 1);
+        assert!(!val.is_rejected());
+        assert_eq!(val.commission(), price(1.0));
+        assert_eq!(val.fees(), price(0.03));
+    }
 }
@@ -20,6 +20,24 @@ pub struct ErrorDetail {
     pub detail: String,
 }
 
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = self.message.iter().map(ToString::to_string).chain(
+            self.errors
+                .iter()
+                .flatten()
+                .map(|x| format!("{} {}: {}", x.status, x.title, x.detail)),
+        );
+        if let Some(first) = parts.next() {
+            write!(f, "{first}")?;
+        }
+        for part in parts {
+            write!(f, "; {part}")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,6 +2,7 @@ use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use super::accounts::AssetType;
+use crate::model::Price;
 
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
@@ -206,7 +207,7 @@ pub struct TransactionOption {
     pub option_deliverables: Vec<TransactionAPIOptionDeliverable>,
     pub option_premium_multiplier: i64,
     pub put_call: TransactionOptionPullCall,
-    pub strike_price: f64,
+    pub strike_price: Price,
     #[serde(rename = "type")]
     pub type_field: TransactionOptionType,
     pub underlying_symbol: String,
@@ -252,7 +253,7 @@ pub struct TransactionBaseInstrument {
     pub closing_price: Option<f64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionCashEquivalentType {
     SweepVehicle,
@@ -261,7 +262,7 @@ pub enum TransactionCashEquivalentType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CollectiveInvestmentType {
     UnitInvestmentTrust,
@@ -271,7 +272,7 @@ pub enum CollectiveInvestmentType {
     Units,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionEquityType {
     CommonStock,
@@ -289,7 +290,7 @@ pub enum TransactionEquityType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionFixedIncomeType {
     BondUnit,
@@ -313,7 +314,7 @@ pub enum TransactionFixedIncomeType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ForexType {
     Standard,
@@ -321,14 +322,14 @@ pub enum ForexType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FutureType {
     Standard,
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum IndexType {
     BroadBased,
@@ -336,7 +337,7 @@ pub enum IndexType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionMutualFundType {
     NotApplicable,
@@ -347,7 +348,7 @@ pub enum TransactionMutualFundType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionOptionPullCall {
     Put,
@@ -355,14 +356,14 @@ pub enum TransactionOptionPullCall {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProductType {
     Tbd,
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionOptionType {
     Vanilla,
@@ -371,7 +372,7 @@ pub enum TransactionOptionType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionType {
     Trade,
@@ -389,9 +390,12 @@ pub enum TransactionType {
     MarginCall,
     MoneyMarket,
     SmaAdjustment,
+    /// Any type not documented by Schwab
+    #[serde(untagged)]
+    Other(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionStatus {
     Valid,
@@ -400,7 +404,7 @@ pub enum TransactionStatus {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionSubAccount {
     Cash,
@@ -411,7 +415,7 @@ pub enum TransactionSubAccount {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionActivityType {
     ActivityCorrection,
@@ -421,7 +425,7 @@ pub enum TransactionActivityType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum UserDetailsType {
     AdvisorUser,
@@ -431,7 +435,7 @@ pub enum UserDetailsType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransferItemFeeType {
     Commission,
@@ -452,7 +456,7 @@ pub enum TransferItemFeeType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransferItemPositionEffect {
     Opening,
@@ -479,6 +483,22 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_de_transaction_type() {
+        assert_eq!(
+            serde_json::from_str::<TransactionType>(r#""TRADE""#).unwrap(),
+            TransactionType::Trade
+        );
+        assert_eq!(
+            serde_json::from_str::<TransactionType>(r#""NEW_TYPE""#).unwrap(),
+            TransactionType::Other("NEW_TYPE".to_string())
+        );
+        assert_eq!(
+            serde_json::to_string(&TransactionType::Other("NEW_TYPE".to_string())).unwrap(),
+            r#""NEW_TYPE""#
+        );
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -41,15 +41,35 @@ pub struct StreamerInfo {
     pub schwab_client_function_id: String,
 }
 
+impl StreamerInfo {
+    /// `Schwab-Client-CorrelId` and `Schwab-Client-Channel` headers identifying the client, e.g. for [`crate::Api::client_headers`]
+    ///
+    /// A value which isn't a valid header value is left out.
+    #[must_use]
+    pub fn client_headers(&self) -> reqwest::header::HeaderMap {
+        [
+            ("schwab-client-correlid", &self.schwab_client_correl_id),
+            ("schwab-client-channel", &self.schwab_client_channel),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = reqwest::header::HeaderValue::from_str(value).ok()?;
+            Some((reqwest::header::HeaderName::from_static(name), value))
+        })
+        .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Offer {
     /// default: false
     pub level_2_permissions: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mkt_data_permission: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum UserPreferenceAccountColor {
     Green,
@@ -76,6 +96,49 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_serde() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/UserPreferences.json"
+        ));
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let val = serde_json::from_value::<UserPreferences>(json.clone()).unwrap();
+
+        assert_json_matches!(
+            val,
+            json,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
+    #[test]
+    fn test_client_headers() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/UserPreferences_real.json"
+        ));
+        let UserPreferences::One(val) = serde_json::from_str::<UserPreferences>(json).unwrap()
+        else {
+            panic!("should be one");
+        };
+
+        let mut info = val.streamer_info[0].clone();
+        let headers = info.client_headers();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(
+            headers["Schwab-Client-CorrelId"],
+            "a12bc34d-e56f-7g8h-i101-2j345678kl90"
+        );
+        assert_eq!(headers["Schwab-Client-Channel"], "A0");
+
+        info.schwab_client_channel = "A0\n".to_string();
+        let headers = info.client_headers();
+        assert_eq!(headers.len(), 1);
+        assert!(!headers.contains_key("Schwab-Client-Channel"));
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -0,0 +1,831 @@
+//! Streaming client over WebSocket.
+//! [API Documentation](https://developer.schwab.com/products/trader-api--individual/details/documentation/Market%20Data%20Production)
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::time::{Instant, Sleep};
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::api::retry::RetryPolicy;
+use crate::error::Error;
+use crate::model::streamer::{
+    Command, Service, StreamerMessage, StreamerRequest, StreamerRequests,
+};
+use crate::model::{LevelOneEquity, LevelOneEquityField, StreamerInfo};
+
+const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// State of the connection to the streamer, see [`Streamer::connection_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// Reconnecting after a disconnect, `attempt` starts from 1.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Disconnected after the reconnect attempts are exhausted.
+    Disconnected,
+}
+
+type Reconnecting = Pin<Box<dyn Future<Output = Result<Connection, Error>> + Send>>;
+
+/// Connection to the Schwab streamer
+///
+/// Received messages are exposed as a [`Stream`] of [`StreamerMessage`].
+///
+/// The connection is considered stale when no message, heartbeats included, is received within the heartbeat timeout.
+/// A disconnect or a stale connection yields an error, after which the stream ends,
+/// unless a reconnect policy is set by [`Streamer::reconnect_policy`].
+/// A message that can't be deserialized is yielded as an error, and the stream goes on.
+pub struct Streamer {
+    connection: Connection,
+    access_token: String,
+    /// Parameters of the active subscriptions, replayed after a reconnect
+    subscriptions: HashMap<Service, HashMap<String, String>>,
+    /// Latest state of each `LEVELONE_EQUITIES` symbol
+    level_one_equities: HashMap<String, LevelOneEquity>,
+    heartbeat_timeout: Option<Duration>,
+    heartbeat: Pin<Box<Sleep>>,
+    reconnect_policy: RetryPolicy,
+    reconnect_attempt: u32,
+    reconnecting: Option<Reconnecting>,
+    state: watch::Sender<ConnectionState>,
+}
+
+impl std::fmt::Debug for Streamer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Streamer")
+            .field("connection", &self.connection)
+            .field("subscriptions", &self.subscriptions)
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .field("reconnect_policy", &self.reconnect_policy)
+            .field("state", &*self.state.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Streamer {
+    /// Connect to `streamer_socket_url` and log in with the access token.
+    pub async fn connect(info: StreamerInfo, access_token: String) -> Result<Self, Error> {
+        let connection = Connection::connect(info, access_token.clone()).await?;
+
+        Ok(Self {
+            connection,
+            access_token,
+            subscriptions: HashMap::new(),
+            level_one_equities: HashMap::new(),
+            heartbeat_timeout: Some(DEFAULT_HEARTBEAT_TIMEOUT),
+            heartbeat: Box::pin(tokio::time::sleep(DEFAULT_HEARTBEAT_TIMEOUT)),
+            reconnect_policy: RetryPolicy::none(),
+            reconnect_attempt: 0,
+            reconnecting: None,
+            state: watch::Sender::new(ConnectionState::Connected),
+        })
+    }
+
+    /// Max duration without any message before the connection is considered stale, `None` to disable.
+    ///
+    /// default: 30 seconds
+    pub fn heartbeat_timeout(&mut self, val: Option<Duration>) -> &mut Self {
+        self.heartbeat_timeout = val;
+        self.reset_heartbeat();
+        self
+    }
+
+    /// Reconnect, log in again and replay the active subscriptions after a disconnect.
+    ///
+    /// `max_retries` is the number of reconnect attempts, delayed by the backoff of the policy.
+    ///
+    /// default: never reconnect
+    pub fn reconnect_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.reconnect_policy = val;
+        self
+    }
+
+    /// Access token used to log in again when reconnecting.
+    ///
+    /// Update it before it expires if the connection is long-lived.
+    pub fn access_token(&mut self, val: String) -> &mut Self {
+        self.access_token = val;
+        self
+    }
+
+    /// Watch the connection state, e.g. to know when a reconnect happened.
+    #[must_use]
+    pub fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Log out, after which the streamer closes the connection.
+    pub async fn logout(mut self) -> Result<(), Error> {
+        self.connection
+            .request(Service::Admin, Command::Logout, None)
+            .await?;
+        self.connection
+            .ws
+            .close(None)
+            .await
+            .map_err(std::convert::Into::into)
+    }
+
+    /// Subscribe to `LEVELONE_EQUITIES`, replacing any previous subscription of this service.
+    ///
+    /// The updates are received by [`Streamer::level_one_equities`].
+    pub async fn subscribe_level_one_equities(
+        &mut self,
+        symbols: &[&str],
+        fields: &[LevelOneEquityField],
+    ) -> Result<(), Error> {
+        let parameters = HashMap::from([
+            ("keys".to_string(), symbols.join(",")),
+            (
+                "fields".to_string(),
+                fields
+                    .iter()
+                    .map(|x| x.id().to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]);
+        self.level_one_equities.clear();
+        self.subscribe(Service::LeveloneEquities, parameters).await
+    }
+
+    async fn subscribe(
+        &mut self,
+        service: Service,
+        parameters: HashMap<String, String>,
+    ) -> Result<(), Error> {
+        self.connection
+            .request(service.clone(), Command::Subs, Some(parameters.clone()))
+            .await?;
+        self.subscriptions.insert(service, parameters);
+        Ok(())
+    }
+
+    /// Stream of `LEVELONE_EQUITIES` updates.
+    ///
+    /// The streamer only sends the fields that changed,
+    /// each item is the latest state of the symbol with the update merged in.
+    ///
+    /// Messages of other services are discarded.
+    pub fn level_one_equities(&mut self) -> impl Stream<Item = Result<LevelOneEquity, Error>> + '_ {
+        futures_util::stream::unfold(
+            (self, VecDeque::new()),
+            |(streamer, mut updates)| async move {
+                loop {
+                    if let Some(update) = updates.pop_front() {
+                        let result = streamer.merge_level_one_equity(update);
+                        return Some((result, (streamer, updates)));
+                    }
+
+                    match streamer.next().await? {
+                        Ok(StreamerMessage::Data(data)) => updates.extend(
+                            data.into_iter()
+                                .filter(|x| x.service == Service::LeveloneEquities)
+                                .flat_map(|x| x.content),
+                        ),
+                        Ok(_) => {}
+                        Err(e) => return Some((Err(e), (streamer, updates))),
+                    }
+                }
+            },
+        )
+    }
+
+    fn merge_level_one_equity(
+        &mut self,
+        update: serde_json::Value,
+    ) -> Result<LevelOneEquity, Error> {
+        let update: LevelOneEquity = serde_json::from_value(update)?;
+        let state = self
+            .level_one_equities
+            .entry(update.key.clone())
+            .or_default();
+        state.update(update);
+        Ok(state.clone())
+    }
+
+    fn reset_heartbeat(&mut self) {
+        if let Some(timeout) = self.heartbeat_timeout {
+            self.heartbeat.as_mut().reset(Instant::now() + timeout);
+        }
+    }
+
+    /// Start reconnecting if the policy allows another attempt.
+    fn try_reconnect(&mut self) -> bool {
+        if self.reconnect_attempt >= self.reconnect_policy.max_retries {
+            warn!("streamer disconnected");
+            self.state.send_replace(ConnectionState::Disconnected);
+            return false;
+        }
+
+        let delay = self.reconnect_policy.backoff(self.reconnect_attempt);
+        warn!(
+            "streamer reconnect {} in {delay:?}",
+            self.reconnect_attempt + 1
+        );
+        let info = self.connection.info.clone();
+        let access_token = self.access_token.clone();
+        let subscriptions = self.subscriptions.clone();
+        self.reconnecting = Some(Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            let mut connection = Connection::connect(info, access_token).await?;
+            for (service, parameters) in subscriptions {
+                connection
+                    .request(service, Command::Subs, Some(parameters))
+                    .await?;
+            }
+            Ok(connection)
+        }));
+
+        self.reconnect_attempt += 1;
+        self.state.send_replace(ConnectionState::Reconnecting {
+            attempt: self.reconnect_attempt,
+        });
+        true
+    }
+}
+
+impl Stream for Streamer {
+    type Item = Result<StreamerMessage, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            // the error of the disconnect was yielded already
+            if self.reconnecting.is_none() && *self.state.borrow() == ConnectionState::Disconnected
+            {
+                return Poll::Ready(None);
+            }
+
+            if let Some(reconnecting) = self.reconnecting.as_mut() {
+                let result = std::task::ready!(reconnecting.as_mut().poll(cx));
+                self.reconnecting = None;
+                match result {
+                    Ok(connection) => {
+                        info!("streamer reconnected");
+                        self.connection = connection;
+                        self.reconnect_attempt = 0;
+                        self.reset_heartbeat();
+                        self.state.send_replace(ConnectionState::Connected);
+                    }
+                    Err(e) => {
+                        warn!("streamer reconnect failed: {e}");
+                        if !self.try_reconnect() {
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if let Some(message) = self.connection.pending.pop_front() {
+                return Poll::Ready(Some(Ok(message)));
+            }
+
+            if let Some(timeout) = self.heartbeat_timeout {
+                if self.heartbeat.as_mut().poll(cx).is_ready() {
+                    if self.try_reconnect() {
+                        continue;
+                    }
+                    return Poll::Ready(Some(Err(Error::Streamer(format!(
+                        "no heartbeat within {timeout:?}"
+                    )))));
+                }
+            }
+
+            return match std::task::ready!(self.connection.poll_ws(cx)) {
+                Some(Ok(message)) => {
+                    self.reset_heartbeat();
+                    Poll::Ready(Some(Ok(message)))
+                }
+                // received, but not modeled, the connection is fine
+                Some(Err(e @ Error::Json(_))) => {
+                    self.reset_heartbeat();
+                    Poll::Ready(Some(Err(e)))
+                }
+                Some(Err(_)) | None if self.try_reconnect() => continue,
+                Some(Err(e)) => Poll::Ready(Some(Err(e))),
+                None => Poll::Ready(Some(Err(Error::Streamer("connection closed".to_string())))),
+            };
+        }
+    }
+}
+
+/// A logged in WebSocket connection
+#[derive(Debug)]
+struct Connection {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    info: StreamerInfo,
+    request_id: u64,
+    /// Messages received while waiting for a response
+    pending: VecDeque<StreamerMessage>,
+}
+
+impl Connection {
+    async fn connect(info: StreamerInfo, access_token: String) -> Result<Self, Error> {
+        // the same client headers as the REST requests, see `StreamerInfo::client_headers`
+        let mut request = info.streamer_socket_url.as_str().into_client_request()?;
+        request.headers_mut().extend(info.client_headers());
+        let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+        let mut connection = Self {
+            ws,
+            info,
+            request_id: 0,
+            pending: VecDeque::new(),
+        };
+        connection.login(access_token).await?;
+        debug!(
+            "streamer logged in to {}",
+            connection.info.streamer_socket_url
+        );
+
+        Ok(connection)
+    }
+
+    async fn login(&mut self, access_token: String) -> Result<(), Error> {
+        let parameters = HashMap::from([
+            ("Authorization".to_string(), access_token),
+            (
+                "SchwabClientChannel".to_string(),
+                self.info.schwab_client_channel.clone(),
+            ),
+            (
+                "SchwabClientFunctionId".to_string(),
+                self.info.schwab_client_function_id.clone(),
+            ),
+        ]);
+        self.request(Service::Admin, Command::Login, Some(parameters))
+            .await
+    }
+
+    /// Send a request and wait for its response.
+    async fn request(
+        &mut self,
+        service: Service,
+        command: Command,
+        parameters: Option<HashMap<String, String>>,
+    ) -> Result<(), Error> {
+        let request_id = self.send(service, command.clone(), parameters).await?;
+
+        loop {
+            let message = std::future::poll_fn(|cx| self.poll_ws(cx))
+                .await
+                .ok_or_else(|| {
+                    Error::Streamer(format!("connection closed before {command:?} response"))
+                })??;
+
+            let StreamerMessage::Response(responses) = &message else {
+                self.pending.push_back(message);
+                continue;
+            };
+            let Some(response) = responses
+                .iter()
+                .find(|x| x.request_id == request_id && x.command == command)
+            else {
+                self.pending.push_back(message);
+                continue;
+            };
+
+            if response.content.code != 0 {
+                return Err(Error::Streamer(format!(
+                    "{command:?} failed with code {}: {}",
+                    response.content.code, response.content.msg
+                )));
+            }
+            return Ok(());
+        }
+    }
+
+    /// Send a request, returns its request ID.
+    async fn send(
+        &mut self,
+        service: Service,
+        command: Command,
+        parameters: Option<HashMap<String, String>>,
+    ) -> Result<String, Error> {
+        let request_id = self.request_id.to_string();
+        self.request_id += 1;
+
+        let requests = StreamerRequests {
+            requests: vec![StreamerRequest {
+                service,
+                request_id: request_id.clone(),
+                command,
+                schwab_client_customer_id: self.info.schwab_client_customer_id.clone(),
+                schwab_client_correl_id: self.info.schwab_client_correl_id.clone(),
+                parameters,
+            }],
+        };
+        let text = serde_json::to_string(&requests)?;
+        self.ws.send(Message::Text(text)).await?;
+
+        Ok(request_id)
+    }
+
+    fn poll_ws(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<StreamerMessage, Error>>> {
+        loop {
+            let message = match Pin::new(&mut self.ws).poll_next(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match message {
+                Message::Text(text) => {
+                    return Poll::Ready(Some(
+                        serde_json::from_str(&text).map_err(std::convert::Into::into),
+                    ))
+                }
+                Message::Close(_) => return Poll::Ready(None),
+                // ping/pong is handled by tungstenite
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::price;
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+
+    fn info(url: String) -> StreamerInfo {
+        StreamerInfo {
+            streamer_socket_url: url,
+            schwab_client_customer_id: "customer".to_string(),
+            schwab_client_correl_id: "correl".to_string(),
+            schwab_client_channel: "N9".to_string(),
+            schwab_client_function_id: "APIAPP".to_string(),
+        }
+    }
+
+    fn response(request: &Value, code: i64) -> Message {
+        let request = &request["requests"][0];
+        Message::Text(
+            json!({
+                "response": [
+                    {
+                        "service": request["service"],
+                        "command": request["command"],
+                        "requestid": request["requestid"],
+                        "SchwabClientCorrelId": "correl",
+                        "timestamp": 1_715_976_000_123_i64,
+                        "content": { "code": code, "msg": "msg" }
+                    }
+                ]
+            })
+            .to_string(),
+        )
+    }
+
+    fn data(content: &Value) -> Message {
+        Message::Text(
+            json!({
+                "data": [
+                    {
+                        "service": "LEVELONE_EQUITIES",
+                        "timestamp": 1_715_976_000_123_i64,
+                        "command": "SUBS",
+                        "content": [content]
+                    }
+                ]
+            })
+            .to_string(),
+        )
+    }
+
+    /// Mock streamer that answers LOGIN with `login_code`, then sends a heartbeat, then answers SUBS with data around the response and LOGOUT.
+    ///
+    /// Accepts `connections` connections in turn, all but the last one are closed after SUBS.
+    async fn server(
+        login_code: i64,
+        connections: usize,
+    ) -> (String, tokio::task::JoinHandle<Vec<Value>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut requests = vec![];
+
+            for i in 0..connections {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let request: Value = serde_json::from_str(&text).unwrap();
+                    let command = request["requests"][0]["command"].clone();
+                    requests.push(request.clone());
+
+                    if command == "LOGIN" {
+                        ws.send(response(&request, login_code)).await.unwrap();
+                        ws.send(Message::Text(
+                            json!({"notify": [{"heartbeat": "1715976000123"}]}).to_string(),
+                        ))
+                        .await
+                        .unwrap();
+                    } else if command == "SUBS" {
+                        // data may arrive before the response
+                        ws.send(data(&json!({"key": "AAPL", "1": 189.5, "2": 189.52})))
+                            .await
+                            .unwrap();
+                        ws.send(response(&request, 0)).await.unwrap();
+                        ws.send(data(&json!({"key": "AAPL", "2": 189.55, "8": 100})))
+                            .await
+                            .unwrap();
+                        if i + 1 < connections {
+                            ws.close(None).await.unwrap();
+                            break;
+                        }
+                    } else {
+                        ws.send(response(&request, 0)).await.unwrap();
+                    }
+                }
+            }
+
+            requests
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_streamer() {
+        let (url, handle) = server(0, 1).await;
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+
+        let message = streamer.next().await.unwrap().unwrap();
+        assert!(matches!(message, StreamerMessage::Notify(_)));
+
+        streamer.logout().await.unwrap();
+
+        let requests = handle.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0],
+            json!({
+                "requests": [
+                    {
+                        "service": "ADMIN",
+                        "requestid": "0",
+                        "command": "LOGIN",
+                        "SchwabClientCustomerId": "customer",
+                        "SchwabClientCorrelId": "correl",
+                        "parameters": {
+                            "Authorization": "token",
+                            "SchwabClientChannel": "N9",
+                            "SchwabClientFunctionId": "APIAPP"
+                        }
+                    }
+                ]
+            })
+        );
+        assert_eq!(requests[1]["requests"][0]["command"], "LOGOUT");
+        assert_eq!(requests[1]["requests"][0]["requestid"], "1");
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)] // the callback signature of tungstenite
+    async fn test_handshake_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut headers = None;
+            let mut ws = tokio_tungstenite::accept_hdr_async(
+                stream,
+                |request: &tokio_tungstenite::tungstenite::handshake::server::Request, response| {
+                    headers = Some(request.headers().clone());
+                    Ok(response)
+                },
+            )
+            .await
+            .unwrap();
+
+            let Some(Ok(Message::Text(text))) = ws.next().await else {
+                panic!("should receive LOGIN");
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            ws.send(response(&request, 0)).await.unwrap();
+            headers.unwrap()
+        });
+
+        let _streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+
+        let headers = handle.await.unwrap();
+        assert_eq!(headers["schwab-client-correlid"], "correl");
+        assert_eq!(headers["schwab-client-channel"], "N9");
+    }
+
+    #[tokio::test]
+    async fn test_streamer_login_failed() {
+        let (url, _handle) = server(3, 1).await;
+
+        let result = Streamer::connect(info(url), "token".to_string()).await;
+        assert!(matches!(result, Err(Error::Streamer(_))));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_level_one_equities() {
+        let (url, handle) = server(0, 1).await;
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+        streamer
+            .subscribe_level_one_equities(
+                &["AAPL", "TSLA"],
+                &[
+                    LevelOneEquityField::Symbol,
+                    LevelOneEquityField::BidPrice,
+                    LevelOneEquityField::AskPrice,
+                    LevelOneEquityField::TotalVolume,
+                ],
+            )
+            .await
+            .unwrap();
+
+        let updates = streamer
+            .level_one_equities()
+            .take(2)
+            .collect::<Vec<_>>()
+            .await;
+        let first = updates[0].as_ref().unwrap();
+        assert_eq!(first.key, "AAPL");
+        assert_eq!(first.ask_price, Some(price(189.52)));
+        assert_eq!(first.total_volume, None);
+        // partial update merged into the latest state
+        let second = updates[1].as_ref().unwrap();
+        assert_eq!(second.bid_price, Some(price(189.5)));
+        assert_eq!(second.ask_price, Some(price(189.55)));
+        assert_eq!(second.total_volume, Some(100));
+
+        streamer.logout().await.unwrap();
+
+        let requests = handle.await.unwrap();
+        assert_eq!(
+            requests[1]["requests"][0],
+            json!({
+                "service": "LEVELONE_EQUITIES",
+                "requestid": "1",
+                "command": "SUBS",
+                "SchwabClientCustomerId": "customer",
+                "SchwabClientCorrelId": "correl",
+                "parameters": {
+                    "keys": "AAPL,TSLA",
+                    "fields": "0,1,2,8"
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnect() {
+        let (url, handle) = server(0, 2).await;
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+        streamer.reconnect_policy(RetryPolicy::new(
+            1,
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+        ));
+        let mut state = streamer.connection_state();
+        streamer
+            .subscribe_level_one_equities(&["AAPL"], &[LevelOneEquityField::BidPrice])
+            .await
+            .unwrap();
+
+        // 2 updates from each connection
+        let updates = streamer
+            .level_one_equities()
+            .take(4)
+            .collect::<Vec<_>>()
+            .await;
+        assert!(updates.iter().all(Result::is_ok));
+        assert!(state.has_changed().unwrap());
+        assert_eq!(*state.borrow_and_update(), ConnectionState::Connected);
+
+        streamer.logout().await.unwrap();
+
+        let requests = handle.await.unwrap();
+        let commands = requests
+            .iter()
+            .map(|x| x["requests"][0]["command"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(commands, ["LOGIN", "SUBS", "LOGIN", "SUBS", "LOGOUT"]);
+        // subscription replayed as is
+        assert_eq!(
+            requests[1]["requests"][0]["parameters"],
+            requests[3]["requests"][0]["parameters"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_without_reconnect() {
+        let (url, _handle) = server(0, 2).await;
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+        let state = streamer.connection_state();
+        streamer
+            .subscribe_level_one_equities(&["AAPL"], &[LevelOneEquityField::BidPrice])
+            .await
+            .unwrap();
+
+        let updates = streamer.level_one_equities().collect::<Vec<_>>().await;
+        assert_eq!(updates.len(), 3);
+        assert!(updates[..2].iter().all(Result::is_ok));
+        assert!(
+            matches!(updates[2], Err(Error::Streamer(ref x)) if x == "connection closed"),
+            "{updates:?}"
+        );
+        assert_eq!(*state.borrow(), ConnectionState::Disconnected);
+        assert!(streamer.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_timeout() {
+        let (url, _handle) = server(0, 1).await;
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+        streamer.heartbeat_timeout(Some(Duration::from_millis(50)));
+
+        let message = streamer.next().await.unwrap();
+        assert!(matches!(message, Ok(StreamerMessage::Notify(_))));
+        let message = streamer.next().await.unwrap();
+        assert!(matches!(message, Err(Error::Streamer(_))));
+        // ended, not waiting for another heartbeat
+        assert!(streamer.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_message() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(text))) = ws.next().await else {
+                panic!("expected LOGIN");
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            ws.send(response(&request, 0)).await.unwrap();
+            ws.send(Message::Text("not a streamer message".to_string()))
+                .await
+                .unwrap();
+            ws.send(Message::Text(
+                json!({"notify": [{"heartbeat": "1715976000123"}]}).to_string(),
+            ))
+            .await
+            .unwrap();
+            // keep the connection open until the client is done
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let mut streamer = Streamer::connect(info(url), "token".to_string())
+            .await
+            .unwrap();
+        streamer.reconnect_policy(RetryPolicy::new(
+            1,
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+        ));
+        let state = streamer.connection_state();
+
+        let message = streamer.next().await.unwrap();
+        assert!(matches!(message, Err(Error::Json(_))), "{message:?}");
+        // the same connection goes on
+        let message = streamer.next().await.unwrap();
+        assert!(
+            matches!(message, Ok(StreamerMessage::Notify(_))),
+            "{message:?}"
+        );
+        assert!(!state.has_changed().unwrap());
+        assert_eq!(*state.borrow(), ConnectionState::Connected);
+
+        drop(streamer);
+        handle.await.unwrap();
+    }
+}
@@ -4,7 +4,7 @@ pub(crate) mod auth;
 pub(crate) mod local_server;
 
 use chrono::TimeDelta;
-use oauth2::TokenResponse;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
@@ -12,18 +12,192 @@ use std::io::{Read, Write};
 use std::path::PathBuf;
 use tokio::sync::Mutex;
 
+use crate::api::DEFAULT_BASE_URL;
 use crate::error::Error;
 use auth::Authorizer;
 
-pub trait Tokener {
+pub trait Tokener: Send + Sync + 'static {
     fn get_access_token(&self) -> impl std::future::Future<Output = Result<String, Error>> + Send;
 
     fn redo_authorization(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+
+    /// Get a new access token without user interaction, e.g. after `401 Unauthorized`.
+    ///
+    /// Fails with [`Error::Unauthorized`] requiring authorization by default.
+    fn refresh_access_token(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send {
+        async {
+            Err(Error::Unauthorized {
+                reauth_required: true,
+            })
+        }
+    }
+}
+
+/// OAuth scope requested when authorizing the app
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// `readonly`, requested by default
+    ReadOnly,
+    /// `api`, the scope Schwab reports on issued tokens
+    Api,
+    /// Any scope not covered above
+    Custom(String),
+}
+
+impl Scope {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ReadOnly => "readonly",
+            Self::Api => "api",
+            Self::Custom(x) => x,
+        }
+    }
 }
 
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Scopes requested unless set by [`TokenCheckerBuilder::scopes`]
+pub const DEFAULT_SCOPES: &[Scope] = &[Scope::ReadOnly];
+
+/// Used when the token response has no `expires_in`, otherwise the last sixth of `expires_in` is cut off the same way.
 const ACCESS_TOKEN_LIFETIME: TimeDelta = TimeDelta::minutes(25); // 25 Minutes instead of 30 min
 const REFRESH_TOKEN_LIFETIME: TimeDelta = TimeDelta::days(6); // 6 days instead of 7 days
 
+/// Builder of [`TokenChecker`], see [`TokenChecker::builder`].
+#[derive(Debug)]
+pub struct TokenCheckerBuilder {
+    path: Option<PathBuf>,
+    client_id: Option<String>,
+    secret: Option<String>,
+    redirect_url: Option<String>,
+    process: auth::AuthProcess,
+    client: Option<Client>,
+    base_url: String,
+    scopes: Vec<Scope>,
+    on_auth_url: Option<auth::AuthUrlObserver>,
+}
+
+impl Default for TokenCheckerBuilder {
+    fn default() -> Self {
+        Self {
+            path: None,
+            client_id: None,
+            secret: None,
+            redirect_url: None,
+            process: auth::AuthProcess::Manual,
+            client: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            scopes: DEFAULT_SCOPES.to_vec(),
+            on_auth_url: None,
+        }
+    }
+}
+
+impl TokenCheckerBuilder {
+    /// Required, where the token is loaded from and saved to.
+    #[must_use]
+    pub fn path(mut self, val: impl Into<PathBuf>) -> Self {
+        self.path = Some(val.into());
+        self
+    }
+
+    /// Required, the app key.
+    #[must_use]
+    pub fn client_id(mut self, val: impl Into<String>) -> Self {
+        self.client_id = Some(val.into());
+        self
+    }
+
+    /// Required, the app secret.
+    #[must_use]
+    pub fn secret(mut self, val: impl Into<String>) -> Self {
+        self.secret = Some(val.into());
+        self
+    }
+
+    /// Required, the callback URL registered for the app, e.g. `https://127.0.0.1:8080`.
+    #[must_use]
+    pub fn redirect_url(mut self, val: impl Into<String>) -> Self {
+        self.redirect_url = Some(val.into());
+        self
+    }
+
+    /// Catch the redirect by a local HTTPS server with the certificate in `certs_dir`.
+    ///
+    /// Without it, the redirect URL is pasted manually as [`TokenChecker::new_with_auth_manually`].
+    #[must_use]
+    pub fn certs_dir(mut self, val: impl Into<PathBuf>) -> Self {
+        self.process = auth::AuthProcess::Auto {
+            certs_dir: val.into(),
+        };
+        self
+    }
+
+    /// Default is [`crate::api::client_builder`].
+    #[must_use]
+    pub fn client(mut self, val: Client) -> Self {
+        self.client = Some(val);
+        self
+    }
+
+    /// Default is [`DEFAULT_BASE_URL`], e.g. a sandbox or a mock server.
+    #[must_use]
+    pub fn base_url(mut self, val: impl Into<String>) -> Self {
+        self.base_url = val.into();
+        self
+    }
+
+    /// Requested on authorization, default is [`DEFAULT_SCOPES`].
+    #[must_use]
+    pub fn scopes(mut self, val: &[Scope]) -> Self {
+        self.scopes = val.to_vec();
+        self
+    }
+
+    /// Called with the authorization URL before the browser is opened, whenever authorization is needed,
+    /// e.g. to log it or to display it in an embedding UI.
+    #[must_use]
+    pub fn on_auth_url(mut self, val: impl Fn(&url::Url) + Send + Sync + 'static) -> Self {
+        self.on_auth_url = Some(auth::AuthUrlObserver(std::sync::Arc::new(val)));
+        self
+    }
+
+    /// Load the token from [`Self::path`], refresh it if the access token expired,
+    /// or authorize if there is no token or the refresh token expired, saving the result.
+    ///
+    /// Fails with [`Error::InvalidConfig`] without one of the required settings,
+    /// and with [`Error::Token`] if the stored token was issued for another app key.
+    pub async fn build(self) -> Result<TokenChecker, Error> {
+        let required = |val: Option<String>, name: &str| {
+            val.ok_or_else(|| Error::InvalidConfig(format!("{name} is required")))
+        };
+        let path = self
+            .path
+            .ok_or_else(|| Error::InvalidConfig("path is required".to_string()))?;
+        let client = match self.client {
+            Some(x) => x,
+            None => crate::api::client_builder().build()?,
+        };
+
+        let auth = Authorizer::new(
+            required(self.client_id, "client_id")?,
+            required(self.secret, "secret")?,
+            required(self.redirect_url, "redirect_url")?,
+            self.process,
+            client,
+            &self.base_url,
+            &self.scopes,
+        )
+        .with_observer(self.on_auth_url);
+        TokenChecker::init(path, auth).await
+    }
+}
+
 #[derive(Debug)]
 pub struct TokenChecker {
     path: PathBuf,
@@ -32,6 +206,26 @@ pub struct TokenChecker {
 }
 
 impl TokenChecker {
+    /// The usual way to get a usable token, see [`TokenCheckerBuilder::build`].
+    ///
+    /// ```no_run
+    /// # async fn f() -> Result<(), schwab_api::Error> {
+    /// let token_checker = schwab_api::token::TokenChecker::builder()
+    ///     .path("token.json")
+    ///     .client_id("your_app_key")
+    ///     .secret("your_secret")
+    ///     .redirect_url("https://127.0.0.1:8080")
+    ///     .certs_dir("your_certs_dir")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn builder() -> TokenCheckerBuilder {
+        TokenCheckerBuilder::default()
+    }
+
     pub async fn new(
         path: PathBuf,
         client_id: String,
@@ -40,79 +234,119 @@ impl TokenChecker {
         certs_dir: PathBuf,
         async_client: Client,
     ) -> Result<Self, Error> {
-        let auth = Authorizer::new(
+        Self::with_base_url(
+            path,
             client_id,
             secret,
             redirect_url,
-            auth::AuthProcess::Auto { certs_dir },
+            Some(certs_dir),
             async_client,
-        );
-        let token = match Token::load(path.clone()) {
-            Ok(token) => token,
-            Err(_) => auth.save(path.clone()).await?,
-        };
+            DEFAULT_BASE_URL,
+        )
+        .await
+    }
 
-        let checker = Self {
+    pub async fn new_with_auth_manually(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        async_client: Client,
+    ) -> Result<Self, Error> {
+        Self::with_base_url(
             path,
-            authorizer: auth,
-            token: Mutex::new(token),
-        };
-
-        checker.check_or_update().await?;
-
-        Ok(checker)
+            client_id,
+            secret,
+            redirect_url,
+            None,
+            async_client,
+            DEFAULT_BASE_URL,
+        )
+        .await
     }
 
-    pub async fn new_with_auth_manually(
+    /// Authorize against another host than [`DEFAULT_BASE_URL`], e.g. a sandbox or a mock server
+    ///
+    /// Without `certs_dir`, authorization is done manually as [`Self::new_with_auth_manually`].
+    pub async fn with_base_url(
         path: PathBuf,
         client_id: String,
         secret: String,
         redirect_url: String,
+        certs_dir: Option<PathBuf>,
         async_client: Client,
+        base_url: &str,
     ) -> Result<Self, Error> {
         let auth = Authorizer::new(
             client_id,
             secret,
             redirect_url,
-            auth::AuthProcess::Manual,
+            Self::auth_process(certs_dir),
             async_client,
+            base_url,
+            DEFAULT_SCOPES,
         );
-        let token = match Token::load(path.clone()) {
-            Ok(token) => token,
-            Err(_) => auth.save(path.clone()).await?,
-        };
+        Self::init(path, auth).await
+    }
 
-        let checker = Self {
+    fn auth_process(certs_dir: Option<PathBuf>) -> auth::AuthProcess {
+        match certs_dir {
+            Some(certs_dir) => auth::AuthProcess::Auto { certs_dir },
+            None => auth::AuthProcess::Manual,
+        }
+    }
+
+    async fn init(path: PathBuf, auth: Authorizer) -> Result<Self, Error> {
+        let token = auth.init(&path).await?;
+
+        Ok(Self {
             path,
             authorizer: auth,
             token: Mutex::new(token),
-        };
-
-        checker.check_or_update().await?;
-
-        Ok(checker)
+        })
     }
 
     async fn check_or_update(&self) -> Result<(), Error> {
         let mut token = self.token.lock().await;
-        if token.is_access_valid() {
-            return Ok(());
-        }
-
-        if token.is_refresh_valid() {
-            if let Ok(rsp) = self.authorizer.access_token(&token.refresh).await {
-                token.access.clone_from(rsp.access_token().secret());
-                token.access_expires_in = chrono::Utc::now()
-                    .checked_add_signed(ACCESS_TOKEN_LIFETIME)
-                    .expect("access_expires_in");
+        self.authorizer.update(&self.path, &mut token).await
+    }
 
-                token.save(self.path.clone())?;
+    /// `Authorization` header to call endpoints not modeled by this crate, the token is refreshed first if needed.
+    ///
+    /// ```no_run
+    /// # async fn f(checker: schwab_api::token::TokenChecker) -> Result<(), schwab_api::Error> {
+    /// let (name, value) = checker.authorization_header().await?;
+    /// let rsp = reqwest::Client::new()
+    ///     .get("https://api.schwabapi.com/trader/v1/userPreference")
+    ///     .header(name, value)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn authorization_header(&self) -> Result<(HeaderName, HeaderValue), Error> {
+        self.check_or_update().await?;
+        self.token.lock().await.authorization_header()
+    }
 
-                return Ok(());
-            }
-        }
+    /// Log out and back in: delete the stored token, then run the browser flow again and save the new token.
+    ///
+    /// Unlike [`Tokener::refresh_access_token`], this needs the user, as the refresh token is replaced too.
+    /// If the flow fails, the old token is gone for good, and the next request authorizes again.
+    pub async fn reauthorize(&self) -> Result<(), Error> {
+        self.redo_authorization_with_observer(|_| {}).await
+    }
 
-        *token = self.authorizer.save(self.path.clone()).await?;
+    /// Same as [`Self::reauthorize`], handing the authorization URL to `on_auth_url` before the browser is opened,
+    /// e.g. to log it or to display it in an embedding UI.
+    pub async fn redo_authorization_with_observer(
+        &self,
+        on_auth_url: impl Fn(&url::Url) + Send + Sync,
+    ) -> Result<(), Error> {
+        let mut token = self.token.lock().await;
+        // expired, so the old token is not handed out if the flow fails
+        *token = Token::default();
+        *token = self.authorizer.reauthorize(&self.path, on_auth_url).await?;
 
         Ok(())
     }
@@ -121,28 +355,63 @@ impl TokenChecker {
 impl Tokener for TokenChecker {
     async fn get_access_token(&self) -> Result<String, Error> {
         self.check_or_update().await?;
-        let access_token = self.token.lock().await.access.clone();
+        let access_token = self.token.lock().await.access.expose().to_string();
         Ok(access_token)
     }
 
     /// must update token in Tokener
+    /// See [`TokenChecker::reauthorize`].
     async fn redo_authorization(&self) -> Result<(), Error> {
+        self.reauthorize().await
+    }
+
+    /// Exchange the refresh token for a new access token, even if the current one has not expired yet.
+    async fn refresh_access_token(&self) -> Result<(), Error> {
         let mut token = self.token.lock().await;
-        *token = self.authorizer.save(self.path.clone()).await?;
+        if !token.is_refresh_valid() {
+            return Err(Error::Unauthorized {
+                reauth_required: true,
+            });
+        }
+        self.authorizer.refresh(&self.path, &mut token).await
+    }
+}
 
-        Ok(())
+/// A token secret, written as is to the token file but never printed by `Debug`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(transparent)]
+struct Secret(String);
+
+impl Secret {
+    fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[redacted]")
     }
 }
 
 // Define a struct to hold the OAuth2 token
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct Token {
-    refresh: String,
+    refresh: Secret,
     refresh_expires_in: chrono::DateTime<chrono::Utc>,
-    access: String,
+    access: Secret,
     access_expires_in: chrono::DateTime<chrono::Utc>,
     #[serde(rename = "type")]
     type_: String,
+    /// SHA-256 of the app key the token was issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    app_key_hash: Option<String>,
 }
 
 impl Token {
@@ -154,19 +423,47 @@ impl Token {
         Ok(token)
     }
 
+    /// Write to a temporary file in the same directory then rename it into place,
+    /// so the token file is never left truncated.
+    ///
+    /// On unix, the file is only readable and writable by the owner.
     fn save(&self, path: PathBuf) -> std::io::Result<()> {
+        static SAVE_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
         // println!("...save token: {}", path.display());
         if let Some(dir) = path.parent() {
             std::fs::create_dir_all(dir)?;
         }
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(path)?;
         let json = serde_json::to_string_pretty(self)?;
-        file.write_all(json.as_bytes())?;
-        Ok(())
+
+        // unique per call, so concurrent saves don't write into the same temporary file
+        let n = SAVE_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(format!(".{}.{n}.tmp", std::process::id()));
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let result = Self::write_file(&tmp_path, json.as_bytes())
+            .and_then(|()| std::fs::rename(&tmp_path, path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    fn write_file(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options.open(path)?;
+        #[cfg(unix)]
+        {
+            // the mode above only applies to a newly created file
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(contents)?;
+        file.sync_all()
     }
 
     fn is_refresh_valid(&self) -> bool {
@@ -176,12 +473,90 @@ impl Token {
     fn is_access_valid(&self) -> bool {
         chrono::Utc::now() < self.access_expires_in
     }
+
+    /// Fails instead of handing out a stale access token.
+    fn authorization_header(&self) -> Result<(HeaderName, HeaderValue), Error> {
+        if !self.is_access_valid() {
+            return Err(Error::Token("access token expired".to_string()));
+        }
+
+        let mut value = HeaderValue::from_str(&format!("{} {}", self.type_, self.access.expose()))
+            .map_err(|e| Error::Token(format!("invalid access token: {e}")))?;
+        value.set_sensitive(true);
+        Ok((AUTHORIZATION, value))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// A directory of its own under the temp dir, removed on drop even if the test fails.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("schwab_api-{name}-{}", std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn token_path(&self) -> PathBuf {
+            self.0.join("token.json")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Token with a valid refresh token `refresh`, and the access token `access` expiring in `access_expires_in`.
+    fn token(access_expires_in: TimeDelta) -> Token {
+        Token {
+            refresh: "refresh".to_string().into(),
+            refresh_expires_in: chrono::Utc::now() + TimeDelta::days(1),
+            access: "access".to_string().into(),
+            access_expires_in: chrono::Utc::now() + access_expires_in,
+            type_: "Bearer".to_string(),
+            app_key_hash: None,
+        }
+    }
+
+    fn authorizer(app_key: &str, base_url: &str) -> Authorizer {
+        Authorizer::new(
+            app_key.to_string(),
+            "SECRET".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            auth::AuthProcess::Manual,
+            Client::new(),
+            base_url,
+            DEFAULT_SCOPES,
+        )
+    }
+
+    /// A checker holding `token`, saved to a [`TempDir`] named by `name` when it changes.
+    fn checker(name: &str, token: Token, base_url: &str) -> (TokenChecker, TempDir) {
+        let dir = TempDir::new(name);
+        let checker = TokenChecker {
+            path: dir.token_path(),
+            authorizer: authorizer("CLIENTID", base_url),
+            token: Mutex::new(token),
+        };
+        (checker, dir)
+    }
+
+    fn builder(path: &std::path::Path, base_url: &str) -> TokenCheckerBuilder {
+        TokenChecker::builder()
+            .path(path)
+            .client_id("CLIENTID")
+            .secret("SECRET")
+            .redirect_url("https://127.0.0.1:8080")
+            .base_url(base_url)
+    }
+
     #[tokio::test]
     #[ignore = "Testing manually for browser verification. Should be --nocapture"]
     async fn test_token_checker_new() {
@@ -229,6 +604,352 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_refresh_token_rotation() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                mockito::Matcher::UrlEncoded("refresh_token".into(), "refresh".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 1800,
+                    "refresh_token": "rotated_refresh",
+                    "scope": "api"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let token = token(-TimeDelta::minutes(1));
+        let refresh_expires_in = token.refresh_expires_in;
+        let (checker, _dir) = checker("rotation", token, &server.url());
+
+        assert_eq!(checker.get_access_token().await.unwrap(), "new_access");
+        mock.assert_async().await;
+
+        let token = Token::load(checker.path.clone()).unwrap();
+        assert_eq!(token.access.expose(), "new_access");
+        assert_eq!(token.refresh.expose(), "rotated_refresh");
+        assert!(token.refresh_expires_in > refresh_expires_in);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_network_error() {
+        // nothing listens on the port once the listener is dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let (checker, _dir) = checker("network-error", token(-TimeDelta::minutes(1)), &base_url);
+
+        // the refresh token is still good, so no authorization is required
+        let result = checker.refresh_access_token().await;
+        assert!(matches!(result, Err(Error::Reqwest(_))), "{result:?}");
+        // and the expired access token is not a reason to run the browser flow
+        let result = checker.get_access_token().await;
+        assert!(matches!(result, Err(Error::Reqwest(_))), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_access_expires_in() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 600
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("expires-in");
+        let mut token = token(-TimeDelta::minutes(1));
+        authorizer("CLIENTID", &server.url())
+            .update(&dir.token_path(), &mut token)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+
+        // 10 minutes from the response instead of ACCESS_TOKEN_LIFETIME, less the last sixth
+        let lifetime = token.access_expires_in - chrono::Utc::now();
+        assert!(lifetime > TimeDelta::seconds(490), "{lifetime}");
+        assert!(lifetime <= TimeDelta::seconds(500), "{lifetime}");
+    }
+
+    #[tokio::test]
+    async fn test_init() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 1800
+                }"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("init");
+        let path = dir.token_path();
+        let auth = authorizer("CLIENTID", &server.url());
+
+        // a valid access token is used as is
+        let mut token = token(TimeDelta::minutes(10));
+        token.save(path.clone()).unwrap();
+        assert_eq!(auth.init(&path).await.unwrap().access.expose(), "access");
+
+        // an expired access token is refreshed and saved
+        token.access_expires_in = chrono::Utc::now() - TimeDelta::minutes(1);
+        token.save(path.clone()).unwrap();
+        assert_eq!(
+            auth.init(&path).await.unwrap().access.expose(),
+            "new_access"
+        );
+        assert_eq!(
+            Token::load(path.clone()).unwrap().access.expose(),
+            "new_access"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_builder() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "grant_type".into(),
+                "refresh_token".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 1800
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("builder");
+        let path = dir.token_path();
+        token(-TimeDelta::minutes(1)).save(path.clone()).unwrap();
+
+        let checker = builder(&path, &server.url()).build().await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(checker.get_access_token().await.unwrap(), "new_access");
+
+        let result = builder(&path, &server.url())
+            .secret(String::new())
+            .client_id("OTHER")
+            .build()
+            .await;
+        assert!(matches!(result, Err(Error::Token(_))), "{result:?}");
+
+        let result = TokenChecker::builder().path(&path).build().await;
+        assert!(
+            matches!(result, Err(Error::InvalidConfig(ref x)) if x == "client_id is required"),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builder_on_auth_url() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("grant_type".into(), "authorization_code".into()),
+                mockito::Matcher::UrlEncoded("code".into(), "code".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 1800,
+                    "refresh_token": "new_refresh"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("observer");
+        let observed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut builder = {
+            let observed = observed.clone();
+            builder(&dir.token_path(), &server.url())
+                .scopes(&[Scope::Api])
+                .on_auth_url(move |x| observed.lock().unwrap().push(x.clone()))
+        };
+        // no token yet, so authorized without a browser
+        builder.process = auth::AuthProcess::Code("code".to_string());
+        let checker = builder.build().await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(checker.get_access_token().await.unwrap(), "new_access");
+
+        let observed = observed.lock().unwrap().clone();
+        assert_eq!(observed.len(), 1);
+        let auth_url = &observed[0];
+        assert!(auth_url.as_str().starts_with(&server.url()));
+        assert_eq!(auth_url.path(), "/v1/oauth/authorize");
+        let pairs: std::collections::HashMap<_, _> = auth_url.query_pairs().collect();
+        assert_eq!(pairs["client_id"], "CLIENTID");
+        assert_eq!(pairs["redirect_uri"], "https://127.0.0.1:8080");
+        assert_eq!(pairs["scope"], "api");
+        assert!(!pairs["state"].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reauthorize() {
+        let mut server = mockito::Server::new_async().await;
+        let dir = TempDir::new("reauthorize");
+        let path = dir.token_path();
+        token(TimeDelta::minutes(10)).save(path.clone()).unwrap();
+
+        let observed = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let mut builder = {
+            let observed = observed.clone();
+            builder(&path, &server.url()).on_auth_url(move |_| *observed.lock().unwrap() += 1)
+        };
+        builder.process = auth::AuthProcess::Code("code".to_string());
+        let checker = builder.build().await.unwrap();
+        // the stored token is usable
+        assert_eq!(checker.get_access_token().await.unwrap(), "access");
+        assert_eq!(*observed.lock().unwrap(), 0);
+
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "grant_type".into(),
+                "authorization_code".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "access_token": "new_access",
+                    "token_type": "Bearer",
+                    "expires_in": 1800,
+                    "refresh_token": "new_refresh"
+                }"#,
+            )
+            .create_async()
+            .await;
+        checker.reauthorize().await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(*observed.lock().unwrap(), 1);
+        assert_eq!(checker.get_access_token().await.unwrap(), "new_access");
+        let token = Token::load(path.clone()).unwrap();
+        assert_eq!(token.refresh.expose(), "new_refresh");
+
+        // a failed flow leaves no token behind
+        mock.remove_async().await;
+        let _mock = server
+            .mock("POST", "/v1/oauth/token")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": "invalid_grant"}"#)
+            .create_async()
+            .await;
+        let observed_by_call = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let result = {
+            let observed_by_call = observed_by_call.clone();
+            checker
+                .redo_authorization_with_observer(move |_| *observed_by_call.lock().unwrap() += 1)
+                .await
+        };
+        assert!(matches!(result, Err(Error::Token(_))), "{result:?}");
+        assert_eq!(*observed.lock().unwrap(), 2);
+        assert_eq!(*observed_by_call.lock().unwrap(), 1);
+        assert!(!path.exists());
+        // the new token is not handed out either, authorizing again is attempted instead
+        assert!(checker.get_access_token().await.is_err());
+        assert_eq!(*observed.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_secret_redacted() {
+        let token = Token {
+            refresh: "refresh_secret".to_string().into(),
+            access: "access_secret".to_string().into(),
+            ..Token::default()
+        };
+        let debug = format!("{token:?}");
+        assert!(!debug.contains("refresh_secret"), "{debug}");
+        assert!(!debug.contains("access_secret"), "{debug}");
+        assert!(debug.contains("[redacted]"), "{debug}");
+
+        // the token file keeps the secrets
+        let json = serde_json::to_value(&token).unwrap();
+        assert_eq!(json["refresh"], "refresh_secret");
+        assert_eq!(json["access"], "access_secret");
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        let mut token = Token {
+            access: "access".to_string().into(),
+            access_expires_in: chrono::Utc::now() + TimeDelta::minutes(10),
+            type_: "Bearer".to_string(),
+            ..Token::default()
+        };
+        let (name, value) = token.authorization_header().unwrap();
+        assert_eq!(name, AUTHORIZATION);
+        assert_eq!(value, "Bearer access");
+        assert!(value.is_sensitive());
+
+        token.access_expires_in = chrono::Utc::now() - TimeDelta::minutes(1);
+        let result = token.authorization_header();
+        assert!(matches!(result, Err(Error::Token(ref x)) if x == "access token expired"));
+    }
+
+    #[tokio::test]
+    async fn test_load_token_app_key() {
+        let dir = TempDir::new("app-key");
+        let path = dir.token_path();
+        let auth = authorizer("CLIENTID", DEFAULT_BASE_URL);
+
+        // a token saved before the hash was stored is accepted
+        Token::default().save(path.clone()).unwrap();
+        let token = auth.load_or_authorize(&path).await.unwrap();
+        assert_eq!(token.app_key_hash.as_deref(), Some(auth.app_key_hash()));
+
+        // the hash is saved but not the app key
+        token.save(path.clone()).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(auth.app_key_hash()));
+        assert!(!contents.contains("CLIENTID"));
+        assert!(auth.load_or_authorize(&path).await.is_ok());
+
+        let result = authorizer("OTHER", DEFAULT_BASE_URL)
+            .load_or_authorize(&path)
+            .await;
+        assert!(
+            matches!(result, Err(Error::Token(ref x)) if x == "token does not match credentials")
+        );
+    }
+
     #[test]
     fn test_save_token() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -237,7 +958,43 @@ mod tests {
             .join("token")
             .join("normal.json");
 
-        Token::save(&Token::default(), path).unwrap();
+        Token::save(&Token::default(), path.clone()).unwrap();
+
+        // no temporary file left behind
+        let dir = path.parent().unwrap();
+        assert!(std::fs::read_dir(dir).unwrap().all(|x| !x
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .ends_with(".tmp")));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_save_token_concurrently() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("schwab")
+            .join("token_concurrent")
+            .join("token.json");
+
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| Token::save(&Token::default(), path.clone()).unwrap());
+            }
+        });
+
+        assert!(Token::load(path.clone()).is_ok());
+        let dir = path.parent().unwrap();
+        assert_eq!(std::fs::read_dir(dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
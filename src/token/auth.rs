@@ -3,23 +3,53 @@ use http::uri::Uri;
 use oauth2::{
     basic::{BasicClient, BasicRequestTokenError, BasicTokenResponse},
     AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, EndpointNotSet, EndpointSet,
-    HttpClientError, RedirectUrl, RefreshToken, Scope, TokenResponse, TokenUrl,
+    HttpClientError, RedirectUrl, RefreshToken, TokenResponse, TokenUrl,
 };
 use reqwest::Client;
 use serde::Deserialize;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use url::Url;
 
 use crate::error::Error;
 use crate::token::local_server;
+use crate::token::Scope;
 use crate::token::Token;
 
 type RequestTokenError = BasicRequestTokenError<HttpClientError<reqwest::Error>>;
 
+/// Expiry of the access token from `expires_in` of the response, falling back to [`super::ACCESS_TOKEN_LIFETIME`].
+///
+/// The last sixth of the lifetime is cut off to refresh a bit early, as [`super::ACCESS_TOKEN_LIFETIME`] does.
+fn access_expires_in(rsp: &BasicTokenResponse) -> chrono::DateTime<chrono::Utc> {
+    let lifetime = rsp
+        .expires_in()
+        .and_then(|x| chrono::TimeDelta::from_std(x).ok())
+        .map_or(super::ACCESS_TOKEN_LIFETIME, |x| x * 5 / 6);
+    chrono::Utc::now()
+        .checked_add_signed(lifetime)
+        .expect("access_expires_in")
+}
+
 #[derive(Debug)]
 pub(super) enum AuthProcess {
-    Auto { certs_dir: PathBuf },
+    Auto {
+        certs_dir: PathBuf,
+    },
     Manual,
+    /// The code is given, to run the flow without a browser in tests
+    #[cfg(test)]
+    Code(String),
+}
+
+/// Called with the authorization URL before the browser is opened, see [`super::TokenCheckerBuilder::on_auth_url`].
+#[derive(Clone)]
+pub(super) struct AuthUrlObserver(pub(super) std::sync::Arc<dyn Fn(&Url) + Send + Sync>);
+
+impl std::fmt::Debug for AuthUrlObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuthUrlObserver")
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +64,10 @@ pub(super) struct Authorizer {
         BasicClient<EndpointSet, EndpointNotSet, EndpointNotSet, EndpointNotSet, EndpointSet>,
     process: AuthProcess,
     async_client: Client,
+    /// SHA-256 of the app key, stored in the token to detect a token issued for other credentials
+    app_key_hash: String,
+    scopes: Vec<Scope>,
+    on_auth_url: Option<AuthUrlObserver>,
 }
 
 impl Authorizer {
@@ -43,12 +77,16 @@ impl Authorizer {
         redirect_url: String,
         process: AuthProcess,
         async_client: Client,
+        base_url: &str,
+        scopes: &[Scope],
     ) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let app_key_hash = format!("{:x}", Sha256::digest(app_key.as_bytes()));
         let app_key = ClientId::new(app_key);
         let secret = ClientSecret::new(secret);
-        let auth_url = AuthUrl::new("https://api.schwabapi.com/v1/oauth/authorize".to_string())
+        let auth_url = AuthUrl::new(format!("{base_url}/v1/oauth/authorize"))
             .expect("Invalid authorization endpoint URL");
-        let token_url = TokenUrl::new("https://api.schwabapi.com/v1/oauth/token".to_string())
+        let token_url = TokenUrl::new(format!("{base_url}/v1/oauth/token"))
             .expect("Invalid token endpoint URL");
         let redirect_url = RedirectUrl::new(redirect_url).expect("Invalid redirect URL");
 
@@ -61,42 +99,66 @@ impl Authorizer {
             oauth2_client,
             process,
             async_client,
+            app_key_hash,
+            scopes: scopes.to_vec(),
+            on_auth_url: None,
         }
     }
 
-    async fn authorize(&self) -> Result<Token, RequestTokenError> {
+    /// Hand the authorization URL to `on_auth_url` on every authorization, before the browser is opened.
+    pub(super) fn with_observer(mut self, on_auth_url: Option<AuthUrlObserver>) -> Self {
+        self.on_auth_url = on_auth_url;
+        self
+    }
+
+    #[cfg(test)]
+    pub(super) fn app_key_hash(&self) -> &str {
+        &self.app_key_hash
+    }
+
+    /// `on_auth_url` is called with the authorization URL before the browser is opened, after the observer set by [`Self::with_observer`].
+    async fn authorize(&self, on_auth_url: impl Fn(&Url)) -> Result<Token, Error> {
         let (auth_url, csrf_token) = self.auth_code_url();
+        if let Some(observer) = &self.on_auth_url {
+            (observer.0)(&auth_url);
+        }
+        on_auth_url(&auth_url);
 
         let auth_code = match &self.process {
             AuthProcess::Auto { certs_dir } => match open::that(auth_url.as_ref()) {
                 Ok(()) => {
-                    println!("Opened '{auth_url}' successfully.");
-                    Self::get_auth_code_with_local_server(csrf_token, certs_dir.clone()).await
+                    info!("opened '{auth_url}' successfully");
+                    Self::get_auth_code_with_local_server(csrf_token, certs_dir.clone()).await?
                 }
                 Err(err) => {
-                    print!("An error occurred when auto opening: {err}");
+                    warn!("failed to open '{auth_url}', falling back to the manual flow: {err}");
                     Self::get_auth_code_manually(&csrf_token, &auth_url)
                 }
             },
             AuthProcess::Manual => Self::get_auth_code_manually(&csrf_token, &auth_url),
+            #[cfg(test)]
+            AuthProcess::Code(code) => AuthorizationCode::new(code.clone()),
         };
 
-        let token_result = self.refresh_token(auth_code).await?;
+        let token_result = self
+            .refresh_token(auth_code)
+            .await
+            .map_err(|e| Error::Token(e.to_string()))?;
         // dbg!(&token_result);
         let token = Token {
             refresh: token_result
                 .refresh_token()
                 .expect("should have refresh_token")
                 .secret()
-                .to_string(),
+                .clone()
+                .into(),
             refresh_expires_in: chrono::Utc::now()
                 .checked_add_signed(super::REFRESH_TOKEN_LIFETIME)
                 .expect("refresh_expires_in"),
-            access: token_result.access_token().secret().to_string(),
-            access_expires_in: chrono::Utc::now()
-                .checked_add_signed(super::ACCESS_TOKEN_LIFETIME)
-                .expect("access_expires_in"),
+            access: token_result.access_token().secret().clone().into(),
+            access_expires_in: access_expires_in(&token_result),
             type_: token_result.token_type().as_ref().to_string(),
+            app_key_hash: Some(self.app_key_hash.clone()),
         };
 
         Ok(token)
@@ -106,7 +168,11 @@ impl Authorizer {
         let (auth_url, csrf_token) = self
             .oauth2_client
             .authorize_url(CsrfToken::new_random)
-            .add_scope(Scope::new("readonly".to_string()))
+            .add_scopes(
+                self.scopes
+                    .iter()
+                    .map(|x| oauth2::Scope::new(x.as_str().to_string())),
+            )
             .url();
         (auth_url, csrf_token)
     }
@@ -114,10 +180,10 @@ impl Authorizer {
     async fn get_auth_code_with_local_server(
         csrf_state: CsrfToken,
         certs_dir: PathBuf,
-    ) -> AuthorizationCode {
-        let code = local_server::local_server(csrf_state, certs_dir).await;
+    ) -> Result<AuthorizationCode, Error> {
+        let code = local_server::local_server(csrf_state, certs_dir).await?;
 
-        AuthorizationCode::new(code)
+        Ok(AuthorizationCode::new(code))
     }
 
     fn get_auth_code_manually(csrf: &CsrfToken, auth_url: &Url) -> AuthorizationCode {
@@ -187,19 +253,131 @@ Redirect URL>"#
     }
 
     pub(super) async fn save(&self, path: PathBuf) -> Result<Token, Error> {
-        let token = self
-            .authorize()
-            .await
-            .map_err(|e| Error::Token(e.to_string()))?;
+        self.save_with_observer(path, |_| {}).await
+    }
+
+    /// Same as [`Self::save`], handing the authorization URL to `on_auth_url` before the browser is opened.
+    pub(super) async fn save_with_observer(
+        &self,
+        path: PathBuf,
+        on_auth_url: impl Fn(&Url),
+    ) -> Result<Token, Error> {
+        let token = self.authorize(on_auth_url).await?;
         token.save(path)?;
         Ok(token)
     }
+
+    /// Authorize from scratch, e.g. when the stored refresh token is bad.
+    ///
+    /// The token at `path` is deleted first, so it is not loaded again if the flow fails or is abandoned.
+    pub(super) async fn reauthorize(
+        &self,
+        path: &Path,
+        on_auth_url: impl Fn(&Url),
+    ) -> Result<Token, Error> {
+        info!(
+            "authorizing again, deleting the token at {}",
+            path.display()
+        );
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        self.save_with_observer(path.to_path_buf(), on_auth_url)
+            .await
+    }
+
+    /// Load the token from `path`, refresh the access token if it expired,
+    /// or authorize again if there is no token or the refresh token expired.
+    ///
+    /// The returned token is usable, and saved to `path` if it changed.
+    pub(super) async fn init(&self, path: &Path) -> Result<Token, Error> {
+        let mut token = self.load_or_authorize(path).await?;
+        self.update(path, &mut token).await?;
+        Ok(token)
+    }
+
+    /// Load the token from `path`, or authorize if there is none.
+    ///
+    /// Fails if the token was issued for another app key.
+    pub(super) async fn load_or_authorize(&self, path: &Path) -> Result<Token, Error> {
+        let Ok(mut token) = Token::load(path.to_path_buf()) else {
+            return self.save(path.to_path_buf()).await;
+        };
+
+        match &token.app_key_hash {
+            Some(hash) if *hash != self.app_key_hash => {
+                Err(Error::Token("token does not match credentials".to_string()))
+            }
+            Some(_) => Ok(token),
+            None => {
+                // saved before the app key hash was stored
+                token.app_key_hash = Some(self.app_key_hash.clone());
+                Ok(token)
+            }
+        }
+    }
+
+    /// Refresh the access token if it expired, or authorize again if the refresh token expired or was rejected.
+    pub(super) async fn update(&self, path: &Path, token: &mut Token) -> Result<(), Error> {
+        if token.is_access_valid() {
+            return Ok(());
+        }
+
+        if token.is_refresh_valid() {
+            debug!("access token expired, refreshing");
+            match self.refresh(path, token).await {
+                Err(Error::Unauthorized { .. }) => {}
+                result => return result,
+            }
+        }
+
+        info!("refresh token expired, authorizing again");
+        *token = self.save(path.to_path_buf()).await?;
+
+        Ok(())
+    }
+
+    /// Exchange the refresh token for a new access token, whether the current one expired or not.
+    ///
+    /// Fails with [`Error::Unauthorized`] if the refresh token was rejected, e.g. revoked,
+    /// and with [`Error::Reqwest`] or [`Error::Timeout`] if the token endpoint could not be reached.
+    pub(super) async fn refresh(&self, path: &Path, token: &mut Token) -> Result<(), Error> {
+        let rsp = self
+            .access_token(token.refresh.expose())
+            .await
+            .map_err(|e| {
+                warn!("failed to refresh the access token: {e}");
+                match e {
+                    // an OAuth error such as `invalid_grant`
+                    RequestTokenError::ServerResponse(_) => Error::Unauthorized {
+                        reauth_required: true,
+                    },
+                    RequestTokenError::Request(HttpClientError::Reqwest(e)) => (*e).into(),
+                    e => Error::Token(e.to_string()),
+                }
+            })?;
+        token.access = rsp.access_token().secret().clone().into();
+        token.access_expires_in = access_expires_in(&rsp);
+
+        // the refresh token may be rotated on each exchange
+        if let Some(refresh) = rsp.refresh_token() {
+            token.refresh = refresh.secret().clone().into();
+            token.refresh_expires_in = chrono::Utc::now()
+                .checked_add_signed(super::REFRESH_TOKEN_LIFETIME)
+                .expect("refresh_expires_in");
+        }
+
+        token.save(path.to_path_buf())?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::api::DEFAULT_BASE_URL;
     use pretty_assertions::assert_eq;
     use std::{borrow::Cow, collections::HashMap};
 
@@ -225,13 +403,15 @@ mod tests {
                 certs_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs"),
             },
             Client::new(),
+            DEFAULT_BASE_URL,
+            &[Scope::ReadOnly],
         );
 
-        let token = auth.authorize().await.unwrap();
+        let token = auth.authorize(|x| println!("{x}")).await.unwrap();
         dbg!(&token);
 
         // test refresh access token
-        let access_token = auth.access_token(&token.refresh).await.unwrap();
+        let access_token = auth.access_token(token.refresh.expose()).await.unwrap();
         dbg!(&access_token);
     }
 
@@ -244,13 +424,21 @@ mod tests {
             REDIRECT_URL.to_string(),
             AuthProcess::Manual,
             Client::new(),
+            DEFAULT_BASE_URL,
+            &[Scope::ReadOnly],
         );
 
-        let token = auth.authorize().await.unwrap();
+        let observed = std::sync::Mutex::new(None);
+        let token = auth
+            .authorize(|x| *observed.lock().unwrap() = Some(x.clone()))
+            .await
+            .unwrap();
+        let observed = observed.into_inner().unwrap().unwrap();
+        assert_eq!(observed.path(), "/v1/oauth/authorize");
         dbg!(&token);
 
         // test refresh access token
-        let access_token = auth.access_token(&token.refresh).await.unwrap();
+        let access_token = auth.access_token(token.refresh.expose()).await.unwrap();
         dbg!(&access_token);
     }
 
@@ -266,6 +454,8 @@ mod tests {
                 certs_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs"),
             },
             Client::new(),
+            DEFAULT_BASE_URL,
+            &[Scope::ReadOnly],
         );
 
         let (auth_url, csrf_token) = auth.auth_code_url();
@@ -299,6 +489,32 @@ mod tests {
         assert!(!csrf_token.secret().is_empty());
     }
 
+    #[test]
+    fn test_auth_code_url_scopes() {
+        let scope = |scopes: &[Scope]| {
+            let auth = Authorizer::new(
+                "CLIENTID".to_string(),
+                "SECRET".to_string(),
+                REDIRECT_URL.to_string(),
+                AuthProcess::Manual,
+                Client::new(),
+                DEFAULT_BASE_URL,
+                scopes,
+            );
+            let (auth_url, _) = auth.auth_code_url();
+            auth_url
+                .query_pairs()
+                .find(|(k, _)| k == "scope")
+                .map(|(_, v)| v.into_owned())
+        };
+
+        assert_eq!(
+            scope(&[Scope::Api, Scope::Custom("other".to_string())]).as_deref(),
+            Some("api other")
+        );
+        assert_eq!(scope(&[]), None);
+    }
+
     #[tokio::test]
     #[ignore = "If the test is performed manually on Linux, it may fail for HTTPS."]
     async fn test_get_auth_code_with_local_server() {
@@ -311,7 +527,7 @@ mod tests {
             .build()
             .unwrap();
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
         let body = client
             .get("https://127.0.0.1:8080/?state=CSRF&code=code")
@@ -321,7 +537,7 @@ mod tests {
             .text()
             .await
             .unwrap();
-        assert_eq!(auth_code.await.unwrap().secret(), "code");
+        assert_eq!(auth_code.await.unwrap().unwrap().secret(), "code");
         assert_eq!(body, "Schwab returned the following code:\ncode\nYou can now safely close this browser window.");
     }
 
@@ -4,26 +4,83 @@ use axum::{
     routing::get,
     Router,
 };
+#[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+use axum_server::tls_openssl::OpenSSLConfig;
+#[cfg(feature = "rustls-tls")]
 use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle;
 use oauth2::CsrfToken;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-pub(super) async fn local_server(csrf: CsrfToken, certs_dir: PathBuf) -> String {
+use crate::error::Error;
+
+/// How long to wait for the browser to be redirected with the code.
+#[allow(clippy::duration_suboptimal_units)] // `Duration::from_mins` needs Rust 1.91
+const TIMEOUT: Duration = Duration::from_secs(300);
+
+pub(super) async fn local_server(csrf: CsrfToken, certs_dir: PathBuf) -> Result<String, Error> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    serve(csrf, &certs_dir, addr, TIMEOUT).await
+}
+
+/// Serve at `addr` until the code is received, an error occurs, or `timeout` elapses.
+///
+/// In every case, the server is shut down and the socket released before returning.
+async fn serve(
+    csrf: CsrfToken,
+    certs_dir: &Path,
+    addr: SocketAddr,
+    timeout: Duration,
+) -> Result<String, Error> {
     let (tx, rx) = async_channel::unbounded();
 
     let app_state = AppState { csrf, tx };
+    let handle = Handle::new();
 
     // configure certificate and private key used by https
-    let config = RustlsConfig::from_pem_file(certs_dir.join("cert.pem"), certs_dir.join("key.pem"))
-        .await
-        .expect("certs setting ok");
+    #[cfg(feature = "rustls-tls")]
+    let mut server = {
+        let config =
+            RustlsConfig::from_pem_file(certs_dir.join("cert.pem"), certs_dir.join("key.pem"))
+                .await?;
+        tokio::spawn(
+            axum_server::bind_rustls(addr, config)
+                .handle(handle.clone())
+                .serve(app(app_state).into_make_service()),
+        )
+    };
+    #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+    let mut server = {
+        let config =
+            OpenSSLConfig::from_pem_file(certs_dir.join("cert.pem"), certs_dir.join("key.pem"))
+                .map_err(|e| Error::Token(format!("invalid certs: {e}")))?;
+        tokio::spawn(
+            axum_server::tls_openssl::bind_openssl(addr, config)
+                .handle(handle.clone())
+                .serve(app(app_state).into_make_service()),
+        )
+    };
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    let result = tokio::select! {
+        code = rx.recv() => code.map_err(|e| Error::Token(format!("failed to receive the code: {e}"))),
+        served = &mut server => Err(match served {
+            Ok(Err(e)) => e.into(),
+            Ok(Ok(())) => Error::Token("local server stopped before receiving the code".to_string()),
+            Err(e) => Error::Token(format!("local server failed: {e}")),
+        }),
+        () = tokio::time::sleep(timeout) => Err(Error::Token("timed out waiting for the code".to_string())),
+    };
 
-    tokio::spawn(axum_server::bind_rustls(addr, config).serve(app(app_state).into_make_service()));
+    // let the response with the code reach the browser, then release the socket
+    handle.graceful_shutdown(Some(Duration::from_secs(1)));
+    if !server.is_finished() {
+        // already reported if it failed
+        let _ = server.await;
+    }
 
-    rx.recv().await.expect("receive code")
+    result
 }
 
 fn app(app_state: AppState) -> Router {
@@ -96,4 +153,69 @@ mod tests {
         );
         assert_eq!(rx.recv().await.unwrap(), "code");
     }
+
+    fn certs_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs")
+    }
+
+    fn free_addr() -> SocketAddr {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serve_twice() {
+        let addr = free_addr();
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+
+        // the second run can only bind if the first one released the socket
+        for code in ["first", "second"] {
+            let csrf = CsrfToken::new_random();
+            let url = format!("https://{addr}/?code={code}&state={}", csrf.secret());
+            let server = tokio::spawn(async move {
+                serve(csrf, &certs_dir(), addr, Duration::from_secs(10)).await
+            });
+
+            let mut rsp = client.get(&url).send().await;
+            while rsp.is_err() && !server.is_finished() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                rsp = client.get(&url).send().await;
+            }
+            assert_eq!(server.await.unwrap().unwrap(), code);
+            assert!(rsp.unwrap().text().await.unwrap().contains(code));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve_timeout() {
+        let addr = free_addr();
+        let result = serve(
+            CsrfToken::new_random(),
+            &certs_dir(),
+            addr,
+            Duration::from_millis(100),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(Error::Token(ref x)) if x == "timed out waiting for the code")
+        );
+        assert!(std::net::TcpListener::bind(addr).is_ok());
+
+        let result = serve(
+            CsrfToken::new_random(),
+            Path::new("missing"),
+            addr,
+            Duration::from_secs(10),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(Error::Stdio(_) | Error::Token(_))),
+            "{result:?}"
+        );
+    }
 }
@@ -0,0 +1,769 @@
+//! End-to-end tests of [`schwab_api::Api`] against a mock Schwab API, no credentials needed.
+
+use mockito::{Matcher, Mock, ServerGuard};
+use pretty_assertions::assert_eq;
+
+use schwab_api::api::parameter::Market;
+use schwab_api::api::retry::RetryPolicy;
+use schwab_api::model::trader::order::OrderStatus;
+use schwab_api::model::{Instruction, InstrumentRequest, OrderRequest};
+use schwab_api::token::{TokenChecker, Tokener};
+use schwab_api::{Api, Error};
+
+const ACCESS_TOKEN: &str = "access_token";
+
+/// Always hands out [`ACCESS_TOKEN`], authorization is never redone.
+#[derive(Debug)]
+struct StaticTokener;
+
+impl Tokener for StaticTokener {
+    async fn get_access_token(&self) -> Result<String, Error> {
+        Ok(ACCESS_TOKEN.to_string())
+    }
+
+    async fn redo_authorization(&self) -> Result<(), Error> {
+        panic!("the mocked token should be accepted");
+    }
+}
+
+fn quote_body(symbol: &str) -> String {
+    let json = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/model/MarketData/QuoteResponse.json"
+    ));
+    let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+    serde_json::json!({ symbol: json[symbol].take() }).to_string()
+}
+
+async fn mock_quote(server: &mut ServerGuard, symbol: &str) -> Mock {
+    server
+        .mock("GET", format!("/marketdata/v1/{symbol}/quotes").as_str())
+        .match_query(Matcher::Any)
+        .match_header("authorization", format!("Bearer {ACCESS_TOKEN}").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body(symbol))
+        .create_async()
+        .await
+}
+
+/// An [`Api`] pointed at `server`, which also answers the quote requested by [`Api::new`] to check the token.
+async fn mock_api(server: &mut ServerGuard) -> Api<StaticTokener> {
+    let mock = mock_quote(server, "AAPL").await;
+    let api = Api::with_base_url(StaticTokener, reqwest::Client::new(), server.url())
+        .await
+        .unwrap();
+    mock.assert_async().await;
+    mock.remove_async().await;
+    api
+}
+
+#[tokio::test]
+async fn test_get_quote() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let mock = mock_quote(&mut server, "BAC").await;
+    let quote = api.get_quote("BAC").await.unwrap().send().await.unwrap();
+    mock.assert_async().await;
+    assert_eq!(quote.symbol(), "BAC");
+}
+
+#[tokio::test]
+async fn test_quote_stream() {
+    use futures_util::StreamExt;
+
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    // a failed poll doesn't end the stream
+    let mock_failed = server
+        .mock("GET", "/marketdata/v1/quotes")
+        .match_query(Matcher::Any)
+        .with_status(400)
+        .with_body("bad request")
+        .expect(1)
+        .create_async()
+        .await;
+    let stream = api.quote_stream(
+        vec!["AAPL".to_string()],
+        std::time::Duration::from_millis(10),
+    );
+    futures_util::pin_mut!(stream);
+    assert!(matches!(stream.next().await, Some(Err(Error::Api(_)))));
+    mock_failed.assert_async().await;
+    mock_failed.remove_async().await;
+
+    let mock = server
+        .mock("GET", "/marketdata/v1/quotes")
+        .match_query(Matcher::UrlEncoded("symbols".into(), "AAPL".into()))
+        .match_header("authorization", format!("Bearer {ACCESS_TOKEN}").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body("AAPL"))
+        .expect(2)
+        .create_async()
+        .await;
+    for _ in 0..2 {
+        let quotes = stream.next().await.unwrap().unwrap();
+        assert_eq!(quotes["AAPL"].symbol(), "AAPL");
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_quote_stream_zero_interval() {
+    use futures_util::StreamExt;
+
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+    let mock = server
+        .mock("GET", "/marketdata/v1/quotes")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body("AAPL"))
+        .expect(2)
+        .create_async()
+        .await;
+
+    // raised to the min interval instead of panicking
+    let stream = api.quote_stream(vec!["AAPL".to_string()], std::time::Duration::ZERO);
+    futures_util::pin_mut!(stream);
+    stream.next().await.unwrap().unwrap();
+    let start = std::time::Instant::now();
+    stream.next().await.unwrap().unwrap();
+    // the period started before the first poll was sent
+    assert!(start.elapsed() >= schwab_api::api::MIN_QUOTE_STREAM_INTERVAL / 2);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_quotes_batched() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let mut body: serde_json::Value = serde_json::from_str(&quote_body("AAPL")).unwrap();
+    let bac: serde_json::Value = serde_json::from_str(&quote_body("BAC")).unwrap();
+    body["BAC"] = bac["BAC"].clone();
+    let mock_chunk = server
+        .mock("GET", "/marketdata/v1/quotes")
+        .match_query(Matcher::UrlEncoded("symbols".into(), "AAPL,BAC".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body.to_string())
+        .create_async()
+        .await;
+    let mock_failed = server
+        .mock("GET", "/marketdata/v1/quotes")
+        .match_query(Matcher::UrlEncoded("symbols".into(), "MRAD".into()))
+        .with_status(400)
+        .with_body("bad request")
+        .create_async()
+        .await;
+
+    let batch = api
+        .get_quotes_batched(["AAPL", "BAC", "MRAD"], Some(2), 2)
+        .await
+        .unwrap();
+    mock_chunk.assert_async().await;
+    mock_failed.assert_async().await;
+
+    let mut symbols = batch.quotes.keys().cloned().collect::<Vec<_>>();
+    symbols.sort();
+    assert_eq!(symbols, ["AAPL", "BAC"]);
+    assert_eq!(batch.failed.len(), 1);
+    assert_eq!(batch.failed[0].0, ["MRAD"]);
+    assert!(matches!(batch.failed[0].1, Error::Api(_)));
+}
+
+#[tokio::test]
+async fn test_get_market_hours_for_date() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let mock = server
+        .mock("GET", "/marketdata/v1/markets")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("markets".into(), "equity,option".into()),
+            Matcher::UrlEncoded("date".into(), "2024-05-18".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/Markets_real.json"
+        )))
+        .create_async()
+        .await;
+
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+    let markets = api
+        .get_market_hours_for_date(vec![Market::Equity, Market::Option], date)
+        .await
+        .unwrap();
+    mock.assert_async().await;
+    assert!(markets.contains_key("equity"));
+    assert!(markets.contains_key("option"));
+}
+
+#[tokio::test]
+async fn test_user_agent() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/marketdata/v1/AAPL/quotes")
+        .match_query(Matcher::Any)
+        .match_header("user-agent", schwab_api::api::DEFAULT_USER_AGENT)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body("AAPL"))
+        .create_async()
+        .await;
+
+    let client = schwab_api::api::client_builder().build().unwrap();
+    Api::with_base_url(StaticTokener, client, server.url())
+        .await
+        .unwrap();
+    mock.assert_async().await;
+    assert!(schwab_api::api::DEFAULT_USER_AGENT.starts_with("schwab_api/"));
+}
+
+#[tokio::test]
+async fn test_client_headers() {
+    let info: schwab_api::model::trader::user_preference::StreamerInfo =
+        serde_json::from_value(serde_json::json!({
+            "streamerSocketUrl": "wss://streamer-api.schwab.com/ws",
+            "schwabClientCustomerId": "customer",
+            "schwabClientCorrelId": "correl",
+            "schwabClientChannel": "N9",
+            "schwabClientFunctionId": "APIAPP",
+        }))
+        .unwrap();
+    let quote_mock = |server: &mut ServerGuard| {
+        server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .match_query(Matcher::Any)
+            .match_header("schwab-client-correlid", "correl")
+            .match_header("schwab-client-channel", "N9")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(quote_body("AAPL"))
+    };
+
+    // set by the builder, the first request included
+    let mut server = mockito::Server::new_async().await;
+    let mock = quote_mock(&mut server).expect(2).create_async().await;
+    let api = Api::builder()
+        .tokener(StaticTokener)
+        .base_url(server.url())
+        .client_headers(info.client_headers())
+        .build()
+        .await
+        .unwrap();
+    api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+    mock.assert_async().await;
+
+    // set later, once the streamer info is known
+    let mut server = mockito::Server::new_async().await;
+    let mut api = mock_api(&mut server).await;
+    api.client_headers(info.client_headers());
+    let mock = quote_mock(&mut server).create_async().await;
+    api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_builder() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/marketdata/v1/AAPL/quotes")
+        .match_query(Matcher::Any)
+        .match_header("user-agent", "dashboard/1.0")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body("AAPL"))
+        .expect(2)
+        .create_async()
+        .await;
+
+    let api = Api::builder()
+        .tokener(StaticTokener)
+        .base_url(server.url())
+        .timeout(std::time::Duration::from_secs(5))
+        .retry_policy(RetryPolicy::none())
+        .user_agent("dashboard/1.0")
+        .build()
+        .await
+        .unwrap();
+    let quote = api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+    mock.assert_async().await;
+    assert_eq!(quote.symbol(), "AAPL");
+
+    let result = Api::<StaticTokener>::builder().build().await;
+    assert!(
+        matches!(result, Err(Error::InvalidConfig(ref x)) if x == "tokener is required"),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_post_account_order() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let mock_account_numbers = server
+        .mock("GET", "/trader/v1/accounts/accountNumbers")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"accountNumber": "12345678", "hashValue": "HASH"}]"#)
+        .create_async()
+        .await;
+
+    let order = OrderRequest::market(
+        InstrumentRequest::Equity {
+            symbol: "AAPL".to_string(),
+        },
+        Instruction::Buy,
+        1.0,
+    )
+    .unwrap();
+    let mock_order = server
+        .mock("POST", "/trader/v1/accounts/HASH/orders")
+        .match_header("authorization", format!("Bearer {ACCESS_TOKEN}").as_str())
+        .match_body(Matcher::Json(serde_json::to_value(&order).unwrap()))
+        .with_status(201)
+        .with_header(
+            "location",
+            &format!("{}/trader/v1/accounts/HASH/orders/1000", server.url()),
+        )
+        .create_async()
+        .await;
+
+    let order_id = api
+        .post_account_order("12345678".to_string(), order)
+        .await
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+    mock_account_numbers.assert_async().await;
+    mock_order.assert_async().await;
+    assert_eq!(order_id, 1000);
+}
+
+fn order_body(status: &str) -> String {
+    let json = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/model/Trader/Order.json"
+    ));
+    let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+    json["status"] = status.into();
+    json.to_string()
+}
+
+#[tokio::test]
+async fn test_get_all_account_orders() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let start = chrono::DateTime::from_timestamp(1_714_000_000, 0).unwrap();
+    let mid = start + schwab_api::api::HISTORY_WINDOW;
+    let end = mid + chrono::TimeDelta::days(10);
+    let orders = |ids: &[i64]| {
+        let orders: Vec<serde_json::Value> = ids
+            .iter()
+            .map(|id| {
+                let mut json: serde_json::Value =
+                    serde_json::from_str(&order_body("FILLED")).unwrap();
+                json["orderId"] = (*id).into();
+                json
+            })
+            .collect();
+        serde_json::Value::from(orders).to_string()
+    };
+
+    let mut mocks = Vec::new();
+    // order 2 is returned by both windows
+    for (from, to, ids) in [(start, mid, [1, 2]), (mid, end, [2, 3])] {
+        let mock = server
+            .mock("GET", "/trader/v1/accounts/HASH/orders")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fromEnteredTime".into(), from.format("%+").to_string()),
+                Matcher::UrlEncoded("toEnteredTime".into(), to.format("%+").to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(orders(&ids))
+            .create_async()
+            .await;
+        mocks.push(mock);
+    }
+
+    let orders = api
+        .get_all_account_orders("HASH".to_string(), start, end)
+        .await
+        .unwrap();
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+    assert_eq!(
+        orders.iter().map(|x| x.order_id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
+
+#[tokio::test]
+async fn test_wait_for_fill() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+    let poll_interval = std::time::Duration::from_millis(10);
+    let timeout = std::time::Duration::from_secs(5);
+
+    let mock_working = server
+        .mock("GET", "/trader/v1/accounts/HASH/orders/1000")
+        .match_header("authorization", format!("Bearer {ACCESS_TOKEN}").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(order_body("WORKING"))
+        .expect(2)
+        .create_async()
+        .await;
+    let mock_filled = server
+        .mock("GET", "/trader/v1/accounts/HASH/orders/1000")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(order_body("FILLED"))
+        .create_async()
+        .await;
+
+    let order = api
+        .wait_for_fill("HASH".to_string(), 1000, poll_interval, timeout)
+        .await
+        .unwrap();
+    mock_working.assert_async().await;
+    mock_filled.assert_async().await;
+    assert!(order.is_filled());
+    mock_filled.remove_async().await;
+
+    let mock = server
+        .mock("GET", "/trader/v1/accounts/HASH/orders/1000")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(order_body("REJECTED"))
+        .create_async()
+        .await;
+    let result = api
+        .wait_for_fill("HASH".to_string(), 1000, poll_interval, timeout)
+        .await;
+    assert!(
+        matches!(&result, Err(Error::OrderNotFilled(x)) if x.status == OrderStatus::Rejected),
+        "{result:?}"
+    );
+    mock.remove_async().await;
+
+    let _mock = server
+        .mock("GET", "/trader/v1/accounts/HASH/orders/1000")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(order_body("WORKING"))
+        .create_async()
+        .await;
+    let result = api
+        .wait_for_fill(
+            "HASH".to_string(),
+            1000,
+            poll_interval,
+            std::time::Duration::from_millis(50),
+        )
+        .await;
+    assert!(
+        matches!(&result, Err(Error::OrderWaitTimeout(x)) if x.status == OrderStatus::Working),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_service_error() {
+    let mut server = mockito::Server::new_async().await;
+    let api = mock_api(&mut server).await;
+
+    let mock = server
+        .mock("GET", "/trader/v1/accounts/HASH")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/ServiceError.json"
+        )))
+        .create_async()
+        .await;
+
+    let result = api
+        .get_account("HASH".to_string())
+        .await
+        .unwrap()
+        .send()
+        .await;
+    mock.assert_async().await;
+    let Err(Error::Service { url, status, .. }) = result else {
+        panic!("should be Error::Service: {result:?}");
+    };
+    assert_eq!(url, format!("{}/trader/v1/accounts/HASH", server.url()));
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_token_refresh() {
+    let mut server = mockito::Server::new_async().await;
+    let mock_token = server
+        .mock("POST", "/v1/oauth/token")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+            Matcher::UrlEncoded("refresh_token".into(), "refresh".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "access_token": "refreshed_access",
+                "token_type": "Bearer",
+                "expires_in": 1800,
+                "scope": "api"
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    // the access token expired, the refresh token is still valid
+    let path = std::env::temp_dir()
+        .join(format!("schwab_api-mock-{}", std::process::id()))
+        .join("token.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let token = serde_json::json!({
+        "refresh": "refresh",
+        "refresh_expires_in": chrono::Utc::now() + chrono::TimeDelta::days(1),
+        "access": "expired_access",
+        "access_expires_in": chrono::Utc::now() - chrono::TimeDelta::minutes(1),
+        "type": "Bearer",
+    });
+    std::fs::write(&path, token.to_string()).unwrap();
+
+    let client = reqwest::Client::new();
+    let token_checker = TokenChecker::with_base_url(
+        path.clone(),
+        "CLIENTID".to_string(),
+        "SECRET".to_string(),
+        "https://127.0.0.1:8080".to_string(),
+        None,
+        client.clone(),
+        &server.url(),
+    )
+    .await
+    .unwrap();
+    mock_token.assert_async().await;
+    let (_, value) = token_checker.authorization_header().await.unwrap();
+    assert_eq!(value, "Bearer refreshed_access");
+
+    let mock_quote = server
+        .mock("GET", "/marketdata/v1/AAPL/quotes")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer refreshed_access")
+        .expect(2)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(quote_body("AAPL"))
+        .create_async()
+        .await;
+    let api = Api::with_base_url(token_checker, client, server.url())
+        .await
+        .unwrap();
+    let quote = api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+    mock_quote.assert_async().await;
+    assert_eq!(quote.symbol(), "AAPL");
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn test_refresh_on_unauthorized() {
+    let mut server = mockito::Server::new_async().await;
+    let quote_mock = |server: &mut ServerGuard, access_token: &str, status: usize| {
+        server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .match_query(Matcher::Any)
+            .match_header("authorization", format!("Bearer {access_token}").as_str())
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(quote_body("AAPL"))
+    };
+
+    // the access token has not expired yet, but Schwab rejects it
+    let path = std::env::temp_dir()
+        .join(format!("schwab_api-mock-refresh-{}", std::process::id()))
+        .join("token.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let token = serde_json::json!({
+        "refresh": "refresh",
+        "refresh_expires_in": chrono::Utc::now() + chrono::TimeDelta::days(1),
+        "access": "rejected_access",
+        "access_expires_in": chrono::Utc::now() + chrono::TimeDelta::minutes(10),
+        "type": "Bearer",
+    });
+    std::fs::write(&path, token.to_string()).unwrap();
+
+    let client = reqwest::Client::new();
+    let token_checker = TokenChecker::with_base_url(
+        path.clone(),
+        "CLIENTID".to_string(),
+        "SECRET".to_string(),
+        "https://127.0.0.1:8080".to_string(),
+        None,
+        client.clone(),
+        &server.url(),
+    )
+    .await
+    .unwrap();
+    let mock = quote_mock(&mut server, "rejected_access", 200)
+        .create_async()
+        .await;
+    let api = Api::with_base_url(token_checker, client, server.url())
+        .await
+        .unwrap();
+    mock.remove_async().await;
+
+    let mock_rejected = quote_mock(&mut server, "rejected_access", 401)
+        .create_async()
+        .await;
+    let mock_token = server
+        .mock("POST", "/v1/oauth/token")
+        .match_body(Matcher::UrlEncoded(
+            "refresh_token".into(),
+            "refresh".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "access_token": "refreshed_access",
+                "token_type": "Bearer",
+                "expires_in": 1800,
+                "scope": "api"
+            }"#,
+        )
+        .create_async()
+        .await;
+    let mock_refreshed = quote_mock(&mut server, "refreshed_access", 200)
+        .create_async()
+        .await;
+
+    // sent once more with the refreshed token
+    let quote = api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+    mock_rejected.assert_async().await;
+    mock_token.assert_async().await;
+    mock_refreshed.assert_async().await;
+    assert_eq!(quote.symbol(), "AAPL");
+
+    // the refresh token was revoked
+    mock_token.remove_async().await;
+    mock_refreshed.remove_async().await;
+    let _mock = quote_mock(&mut server, "refreshed_access", 401)
+        .create_async()
+        .await;
+    let _mock = server
+        .mock("POST", "/v1/oauth/token")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error": "invalid_grant"}"#)
+        .create_async()
+        .await;
+
+    let result = api.get_quote("AAPL").await.unwrap().send().await;
+    assert!(
+        matches!(
+            result,
+            Err(Error::Unauthorized {
+                reauth_required: true
+            })
+        ),
+        "{result:?}"
+    );
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn test_blocking_refresh_on_unauthorized() {
+    let mut server = mockito::Server::new();
+    let quote_mock = |server: &mut ServerGuard, access_token: &str, status: usize| {
+        server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .match_query(Matcher::Any)
+            .match_header("authorization", format!("Bearer {access_token}").as_str())
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(quote_body("AAPL"))
+    };
+
+    let path = std::env::temp_dir()
+        .join(format!(
+            "schwab_api-mock-refresh-blocking-{}",
+            std::process::id()
+        ))
+        .join("token.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let token = serde_json::json!({
+        "refresh": "refresh",
+        "refresh_expires_in": chrono::Utc::now() + chrono::TimeDelta::days(1),
+        "access": "rejected_access",
+        "access_expires_in": chrono::Utc::now() + chrono::TimeDelta::minutes(10),
+        "type": "Bearer",
+    });
+    std::fs::write(&path, token.to_string()).unwrap();
+
+    let client = reqwest::Client::new();
+    let token_checker = tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(TokenChecker::with_base_url(
+            path.clone(),
+            "CLIENTID".to_string(),
+            "SECRET".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            None,
+            client.clone(),
+            &server.url(),
+        ))
+        .unwrap();
+    let mock = quote_mock(&mut server, "rejected_access", 200).create();
+    let api =
+        schwab_api::blocking::Api::with_base_url(token_checker, client, server.url()).unwrap();
+    mock.remove();
+
+    let mock_rejected = quote_mock(&mut server, "rejected_access", 401).create();
+    let mock_token = server
+        .mock("POST", "/v1/oauth/token")
+        .match_body(Matcher::UrlEncoded(
+            "refresh_token".into(),
+            "refresh".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "access_token": "refreshed_access",
+                "token_type": "Bearer",
+                "expires_in": 1800,
+                "scope": "api"
+            }"#,
+        )
+        .create();
+    let mock_refreshed = quote_mock(&mut server, "refreshed_access", 200).create();
+
+    let quote = api.get_quote("AAPL").unwrap().send().unwrap();
+    mock_rejected.assert();
+    mock_token.assert();
+    mock_refreshed.assert();
+    assert_eq!(quote.symbol(), "AAPL");
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}